@@ -0,0 +1,179 @@
+mod tar;
+use tar::*;
+mod zip;
+use zip::*;
+
+use crate::utils::{pipe, PipeWriter};
+use crate::{Download, Upload};
+use async_compression::futures::bufread::{GzipDecoder, ZstdDecoder};
+use async_compression::futures::write::{GzipEncoder, ZstdEncoder};
+use futures::io::AsyncBufRead;
+use futures::AsyncWriteExt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGzip,
+    TarZstd,
+    Zip,
+}
+
+pub fn pack(
+    paths: Vec<PathBuf>,
+    format: ArchiveFormat,
+) -> Box<dyn AsyncBufRead + Send + Sync + Unpin> {
+    let (mut writer, reader) = pipe(16);
+
+    async_std::task::spawn(async move {
+        match pack_into(paths.as_slice(), format, &mut writer).await {
+            Ok(_) => {
+                let _ = writer.close().await;
+            }
+            Err(e) => writer.fail(e).await,
+        }
+    });
+
+    Box::new(reader)
+}
+
+async fn pack_into(
+    paths: &[PathBuf],
+    format: ArchiveFormat,
+    writer: &mut PipeWriter,
+) -> io::Result<()> {
+    match format {
+        ArchiveFormat::Tar => {
+            pack_tar(paths, writer).await?;
+        }
+        ArchiveFormat::TarGzip => {
+            let mut encoder = pack_tar(paths, GzipEncoder::new(writer)).await?;
+            encoder.close().await?;
+        }
+        ArchiveFormat::TarZstd => {
+            let mut encoder = pack_tar(paths, ZstdEncoder::new(writer)).await?;
+            encoder.close().await?;
+        }
+        ArchiveFormat::Zip => {
+            let mut zip_writer = ZipWriter::new(writer);
+            for path in paths {
+                let name = entry_name(path)?;
+                zip_writer.append_path(name.as_str(), path).await?;
+            }
+            zip_writer.finish().await?;
+        }
+    };
+
+    Ok(())
+}
+
+pub async fn unpack(
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    format: ArchiveFormat,
+    dst: &Path,
+) -> anyhow::Result<()> {
+    async_std::fs::create_dir_all(dst).await?;
+
+    match format {
+        ArchiveFormat::Tar => unpack_tar(reader, dst).await?,
+        ArchiveFormat::TarGzip => unpack_tar(GzipDecoder::new(reader), dst).await?,
+        ArchiveFormat::TarZstd => unpack_tar(ZstdDecoder::new(reader), dst).await?,
+        ArchiveFormat::Zip => unpack_zip(reader, dst).await?,
+    };
+
+    Ok(())
+}
+
+pub async fn upload<'a, S: Upload>(
+    name: &'a str,
+    paths: Vec<PathBuf>,
+    format: ArchiveFormat,
+    setting: S::UploadSetting,
+    auth_token: &'a S::AuthToken,
+) -> anyhow::Result<S::File> {
+    let reader = pack(paths, format);
+    S::upload(name, reader, None, setting, auth_token).await
+}
+
+pub async fn download<'a, S: Download>(
+    file: S::File,
+    setting: S::DownloadSetting,
+    auth_token: &'a S::AuthToken,
+    format: ArchiveFormat,
+    dst: &'a Path,
+) -> anyhow::Result<()> {
+    let reader = S::download(file, setting, auth_token).await?;
+    unpack(reader, format, dst).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArchiveFormat;
+    use async_std::fs;
+    use futures::AsyncReadExt;
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("hearthbeat-archive-{}", rand::random::<u64>()))
+    }
+
+    #[tokio::test]
+    async fn pack_unpack() -> anyhow::Result<()> {
+        struct TestCase {
+            format: ArchiveFormat,
+        }
+
+        let testcases = [
+            TestCase {
+                format: ArchiveFormat::Tar,
+            },
+            TestCase {
+                format: ArchiveFormat::TarGzip,
+            },
+            TestCase {
+                format: ArchiveFormat::TarZstd,
+            },
+            TestCase {
+                format: ArchiveFormat::Zip,
+            },
+        ];
+
+        for testcase in testcases {
+            let src = temp_dir();
+            fs::create_dir_all(src.join("build/nested")).await?;
+            fs::write(src.join("build/a.txt"), "abcd").await?;
+            fs::write(src.join("build/nested/b.bin"), vec![7u8; 200 * 1024]).await?;
+            fs::write(src.join("notes.txt"), "notes").await?;
+
+            let reader = super::pack(
+                vec![src.join("build"), src.join("notes.txt")],
+                testcase.format,
+            );
+
+            let dst = temp_dir();
+            super::unpack(reader, testcase.format, dst.as_path()).await?;
+
+            assert_eq!(fs::read(dst.join("build/a.txt")).await?, b"abcd");
+            assert_eq!(
+                fs::read(dst.join("build/nested/b.bin")).await?,
+                vec![7u8; 200 * 1024]
+            );
+            assert_eq!(fs::read(dst.join("notes.txt")).await?, b"notes");
+
+            fs::remove_dir_all(src).await?;
+            fs::remove_dir_all(dst).await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pack_missing_path() -> anyhow::Result<()> {
+        let mut reader = super::pack(vec![temp_dir()], ArchiveFormat::Tar);
+        let mut data = vec![];
+        assert!(reader.read_to_end(&mut data).await.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use async_std::fs;
+use async_tar::{Archive, Builder};
+use futures::io::{AsyncRead, AsyncWrite};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub async fn pack_tar<W: AsyncWrite + Unpin + Send + Sync>(
+    paths: &[PathBuf],
+    writer: W,
+) -> io::Result<W> {
+    let mut builder = Builder::new(writer);
+
+    for path in paths {
+        let name = entry_name(path)?;
+        if fs::metadata(path).await?.is_dir() {
+            builder.append_dir_all(name, path).await?;
+        } else {
+            builder.append_path_with_name(path, name).await?;
+        }
+    }
+
+    builder.into_inner().await
+}
+
+pub async fn unpack_tar<R: AsyncRead + Unpin>(reader: R, dst: &Path) -> io::Result<()> {
+    Archive::new(reader).unpack(dst).await
+}
+
+pub fn entry_name(path: &Path) -> io::Result<String> {
+    match path.file_name() {
+        Some(v) => Ok(v.to_string_lossy().to_string()),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unable to name {}.", path.display()),
+        )),
+    }
+}
@@ -0,0 +1,446 @@
+use async_std::fs::{self, File};
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Decompress, FlushDecompress, Status};
+use futures::io::{AsyncBufRead, AsyncRead, AsyncWrite};
+use futures::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, StreamExt};
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+const LOCAL_FILE_HEADER: u32 = 0x04034b50;
+const DATA_DESCRIPTOR: u32 = 0x08074b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+const FLAG_UTF8: u16 = 0x0800;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+const VERSION_NEEDED: u16 = 20;
+const VERSION_MADE_BY: u16 = (3 << 8) | VERSION_NEEDED;
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+struct ZipEntry {
+    name: String,
+    flags: u16,
+    method: u16,
+    crc32: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+    external_attributes: u32,
+}
+
+pub struct ZipWriter<W> {
+    writer: W,
+    entries: Vec<ZipEntry>,
+    offset: u64,
+}
+
+impl<W: AsyncWrite + Unpin> ZipWriter<W> {
+    pub fn new(writer: W) -> ZipWriter<W> {
+        ZipWriter {
+            writer,
+            entries: vec![],
+            offset: 0,
+        }
+    }
+
+    pub async fn append_path<'a>(&mut self, name: &'a str, path: &'a Path) -> io::Result<()> {
+        let mut pending: Vec<(String, PathBuf)> = vec![(name.to_string(), path.to_path_buf())];
+
+        while let Some((name, path)) = pending.pop() {
+            let metadata = fs::metadata(&path).await?;
+            if !metadata.is_dir() {
+                let file = File::open(&path).await?;
+                self.append_reader(name.as_str(), file).await?;
+                continue;
+            }
+
+            self.append_dir(name.as_str()).await?;
+
+            let mut children: Vec<(String, PathBuf)> = vec![];
+            let mut dir = fs::read_dir(&path).await?;
+            while let Some(child) = dir.next().await {
+                let child = child?;
+                let child_name = format!("{}/{}", name, child.file_name().to_string_lossy());
+                children.push((child_name, child.path().into()));
+            }
+            children.sort_by(|a, b| b.0.cmp(&a.0));
+            pending.append(&mut children);
+        }
+
+        Ok(())
+    }
+
+    pub async fn append_dir(&mut self, name: &str) -> io::Result<()> {
+        let name = format!("{}/", name.trim_end_matches('/'));
+        let entry = ZipEntry {
+            name,
+            flags: FLAG_UTF8,
+            method: METHOD_STORED,
+            crc32: 0,
+            compressed_size: 0,
+            size: 0,
+            offset: self.current_offset()?,
+            external_attributes: (0o40755 << 16) | 0x10,
+        };
+        self.write_local_header(&entry).await?;
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    pub async fn append_reader<R: AsyncRead + Unpin>(
+        &mut self,
+        name: &str,
+        mut reader: R,
+    ) -> io::Result<()> {
+        let mut entry = ZipEntry {
+            name: name.to_string(),
+            flags: FLAG_UTF8 | FLAG_DATA_DESCRIPTOR,
+            method: METHOD_DEFLATED,
+            crc32: 0,
+            compressed_size: 0,
+            size: 0,
+            offset: self.current_offset()?,
+            external_attributes: 0o100644 << 16,
+        };
+        self.write_local_header(&entry).await?;
+
+        let mut hasher = crc32fast::Hasher::new();
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        let mut size: u64 = 0;
+        let mut compressed_size: u64 = 0;
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = reader.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            size += n as u64;
+            encoder.write_all(&buf[..n])?;
+
+            let compressed = std::mem::take(encoder.get_mut());
+            compressed_size += compressed.len() as u64;
+            self.write(compressed.as_slice()).await?;
+        }
+        let compressed = encoder.finish()?;
+        compressed_size += compressed.len() as u64;
+        self.write(compressed.as_slice()).await?;
+
+        entry.crc32 = hasher.finalize();
+        entry.size = to_u32(size)?;
+        entry.compressed_size = to_u32(compressed_size)?;
+
+        let mut descriptor = Vec::with_capacity(16);
+        descriptor.extend_from_slice(&DATA_DESCRIPTOR.to_le_bytes());
+        descriptor.extend_from_slice(&entry.crc32.to_le_bytes());
+        descriptor.extend_from_slice(&entry.compressed_size.to_le_bytes());
+        descriptor.extend_from_slice(&entry.size.to_le_bytes());
+        self.write(descriptor.as_slice()).await?;
+
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    pub async fn finish(mut self) -> io::Result<W> {
+        let central_directory_offset = self.current_offset()?;
+
+        let mut central_directory = vec![];
+        for entry in self.entries.iter() {
+            central_directory.extend_from_slice(&CENTRAL_DIRECTORY_HEADER.to_le_bytes());
+            central_directory.extend_from_slice(&VERSION_MADE_BY.to_le_bytes());
+            central_directory.extend_from_slice(&VERSION_NEEDED.to_le_bytes());
+            central_directory.extend_from_slice(&entry.flags.to_le_bytes());
+            central_directory.extend_from_slice(&entry.method.to_le_bytes());
+            central_directory.extend_from_slice(&0u16.to_le_bytes());
+            central_directory.extend_from_slice(&DOS_DATE.to_le_bytes());
+            central_directory.extend_from_slice(&entry.crc32.to_le_bytes());
+            central_directory.extend_from_slice(&entry.compressed_size.to_le_bytes());
+            central_directory.extend_from_slice(&entry.size.to_le_bytes());
+            central_directory.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            central_directory.extend_from_slice(&0u16.to_le_bytes());
+            central_directory.extend_from_slice(&0u16.to_le_bytes());
+            central_directory.extend_from_slice(&0u16.to_le_bytes());
+            central_directory.extend_from_slice(&0u16.to_le_bytes());
+            central_directory.extend_from_slice(&entry.external_attributes.to_le_bytes());
+            central_directory.extend_from_slice(&entry.offset.to_le_bytes());
+            central_directory.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let entries: u16 = match self.entries.len().try_into() {
+            Ok(v) => v,
+            Err(_e) => return Err(zip64_error()),
+        };
+        let central_directory_size = to_u32(central_directory.len() as u64)?;
+
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        end.extend_from_slice(&entries.to_le_bytes());
+        end.extend_from_slice(&entries.to_le_bytes());
+        end.extend_from_slice(&central_directory_size.to_le_bytes());
+        end.extend_from_slice(&central_directory_offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+
+        self.write(central_directory.as_slice()).await?;
+        self.write(end.as_slice()).await?;
+        self.writer.flush().await?;
+
+        Ok(self.writer)
+    }
+
+    async fn write_local_header(&mut self, entry: &ZipEntry) -> io::Result<()> {
+        if entry.name.len() > u16::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "entry name is too long.",
+            ));
+        }
+
+        let mut header = Vec::with_capacity(30 + entry.name.len());
+        header.extend_from_slice(&LOCAL_FILE_HEADER.to_le_bytes());
+        header.extend_from_slice(&VERSION_NEEDED.to_le_bytes());
+        header.extend_from_slice(&entry.flags.to_le_bytes());
+        header.extend_from_slice(&entry.method.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&entry.crc32.to_le_bytes());
+        header.extend_from_slice(&entry.compressed_size.to_le_bytes());
+        header.extend_from_slice(&entry.size.to_le_bytes());
+        header.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(entry.name.as_bytes());
+
+        self.write(header.as_slice()).await
+    }
+
+    async fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.writer.write_all(data).await?;
+        self.offset += data.len() as u64;
+        Ok(())
+    }
+
+    fn current_offset(&self) -> io::Result<u32> {
+        to_u32(self.offset)
+    }
+}
+
+pub async fn unpack_zip<R: AsyncBufRead + Unpin>(mut reader: R, dst: &Path) -> io::Result<()> {
+    loop {
+        let signature = read_u32(&mut reader).await?;
+        if signature == CENTRAL_DIRECTORY_HEADER || signature == END_OF_CENTRAL_DIRECTORY {
+            break;
+        }
+        if signature != LOCAL_FILE_HEADER {
+            return Err(invalid_data("unable to recognize the zip entry."));
+        }
+
+        let mut header = [0u8; 26];
+        reader.read_exact(&mut header).await?;
+        let flags = u16::from_le_bytes([header[2], header[3]]);
+        let method = u16::from_le_bytes([header[4], header[5]]);
+        let crc32 = u32::from_le_bytes([header[10], header[11], header[12], header[13]]);
+        let compressed_size = u32::from_le_bytes([header[14], header[15], header[16], header[17]]);
+        let name_len = u16::from_le_bytes([header[22], header[23]]) as usize;
+        let extra_len = u16::from_le_bytes([header[24], header[25]]) as usize;
+
+        let name = {
+            let mut name = vec![0u8; name_len];
+            reader.read_exact(&mut name).await?;
+            String::from_utf8_lossy(name.as_slice()).to_string()
+        };
+        {
+            let mut extra = vec![0u8; extra_len];
+            reader.read_exact(&mut extra).await?;
+        }
+
+        let path = entry_path(dst, name.as_str())?;
+        if name.ends_with('/') {
+            fs::create_dir_all(&path).await?;
+        } else if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let written_crc32 = if name.ends_with('/') && compressed_size == 0 {
+            0
+        } else {
+            let mut file = File::create(&path).await?;
+            let written_crc32 = match method {
+                METHOD_DEFLATED => inflate(&mut reader, &mut file).await?,
+                METHOD_STORED if flags & FLAG_DATA_DESCRIPTOR == 0 => {
+                    copy_exact(&mut reader, &mut file, compressed_size as u64).await?
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        format!("unsupported zip entry {}.", name),
+                    ))
+                }
+            };
+            file.flush().await?;
+            written_crc32
+        };
+
+        let crc32 = if flags & FLAG_DATA_DESCRIPTOR != 0 {
+            let first = read_u32(&mut reader).await?;
+            let crc32 = if first == DATA_DESCRIPTOR {
+                read_u32(&mut reader).await?
+            } else {
+                first
+            };
+            read_u32(&mut reader).await?;
+            read_u32(&mut reader).await?;
+            crc32
+        } else {
+            crc32
+        };
+
+        if crc32 != written_crc32 {
+            return Err(invalid_data("zip entry checksum do not match."));
+        }
+    }
+
+    Ok(())
+}
+
+async fn inflate<R: AsyncBufRead + Unpin>(reader: &mut R, file: &mut File) -> io::Result<u32> {
+    let mut decompress = Decompress::new(false);
+    let mut hasher = crc32fast::Hasher::new();
+    let mut out = vec![0u8; 64 * 1024];
+
+    loop {
+        let input = reader.fill_buf().await?;
+        let eof = input.is_empty();
+        let total_in = decompress.total_in();
+        let total_out = decompress.total_out();
+        let flush = if eof {
+            FlushDecompress::Finish
+        } else {
+            FlushDecompress::None
+        };
+        let status = match decompress.decompress(input, &mut out, flush) {
+            Ok(v) => v,
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+        let consumed = (decompress.total_in() - total_in) as usize;
+        let produced = (decompress.total_out() - total_out) as usize;
+        reader.consume_unpin(consumed);
+
+        hasher.update(&out[..produced]);
+        file.write_all(&out[..produced]).await?;
+
+        if status == Status::StreamEnd {
+            break;
+        }
+        if eof && consumed == 0 && produced == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+    }
+
+    Ok(hasher.finalize())
+}
+
+async fn copy_exact<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    file: &mut File,
+    len: u64,
+) -> io::Result<u32> {
+    let mut hasher = crc32fast::Hasher::new();
+    let mut remaining = len;
+
+    while remaining > 0 {
+        let input = reader.fill_buf().await?;
+        if input.is_empty() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let n = std::cmp::min(input.len() as u64, remaining) as usize;
+        hasher.update(&input[..n]);
+        file.write_all(&input[..n]).await?;
+        reader.consume_unpin(n);
+        remaining -= n as u64;
+    }
+
+    Ok(hasher.finalize())
+}
+
+async fn read_u32<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).await?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn entry_path<'a>(dst: &'a Path, name: &'a str) -> io::Result<PathBuf> {
+    let mut path = dst.to_path_buf();
+    for component in Path::new(name.trim_end_matches('/')).components() {
+        match component {
+            Component::Normal(v) => path.push(v),
+            Component::CurDir => {}
+            _ => return Err(invalid_data("zip entry escapes the destination.")),
+        }
+    }
+
+    Ok(path)
+}
+
+fn to_u32(value: u64) -> io::Result<u32> {
+    match value.try_into() {
+        Ok(v) => Ok(v),
+        Err(_e) => Err(zip64_error()),
+    }
+}
+
+fn zip64_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "zip64 is not supported.")
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn entry_path() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            name: &'a str,
+            path: Option<PathBuf>,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "dir/file.txt",
+                path: Some(PathBuf::from("/dst/dir/file.txt")),
+            },
+            TestCase {
+                name: "dir/",
+                path: Some(PathBuf::from("/dst/dir")),
+            },
+            TestCase {
+                name: "../file.txt",
+                path: None,
+            },
+            TestCase {
+                name: "/etc/passwd",
+                path: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let path = super::entry_path(Path::new("/dst"), testcase.name);
+            match testcase.path {
+                Some(v) => assert_eq!(path?, v),
+                None => assert!(path.is_err()),
+            }
+        }
+
+        Ok(())
+    }
+}
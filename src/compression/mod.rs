@@ -0,0 +1,66 @@
+mod stream;
+pub use stream::*;
+
+use crate::{Download, Service, Upload};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use std::marker::PhantomData;
+
+pub struct Compressed<S> {
+    _service: PhantomData<S>,
+}
+
+pub struct UploadSetting<T> {
+    pub inner: T,
+    pub codec: Codec,
+}
+
+impl<S: Service> Service for Compressed<S> {
+    type AuthToken = S::AuthToken;
+    type File = S::File;
+}
+
+#[async_trait]
+impl<S> Download for Compressed<S>
+where
+    S: Download,
+    S::File: Send,
+    S::DownloadSetting: Send,
+    S::AuthToken: Sync,
+{
+    type DownloadSetting = S::DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        let reader = S::download(file, setting, auth_token).await?;
+        decompress(reader).await
+    }
+}
+
+#[async_trait]
+impl<S> Upload for Compressed<S>
+where
+    S: Upload,
+    S::UploadSetting: Send,
+    S::AuthToken: Sync,
+{
+    type UploadSetting = UploadSetting<S::UploadSetting>;
+
+    fn max_file_size(auth_token: &Self::AuthToken) -> usize {
+        S::max_file_size(auth_token)
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        _len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let reader = compress(reader, setting.codec);
+        S::upload(name, reader, None, setting.inner, auth_token).await
+    }
+}
@@ -0,0 +1,145 @@
+use async_compression::futures::bufread::{
+    GzipDecoder, GzipEncoder, XzDecoder, XzEncoder, ZstdDecoder, ZstdEncoder,
+};
+use futures::io::{AsyncBufRead, BufReader, Cursor};
+use futures::AsyncReadExt;
+
+const MAGIC: &[u8; 4] = b"HBZ\x01";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Codec {
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Codec {
+    fn id(&self) -> u8 {
+        match self {
+            Codec::Gzip => 1,
+            Codec::Zstd => 2,
+            Codec::Xz => 3,
+        }
+    }
+
+    fn from_id(id: u8) -> anyhow::Result<Codec> {
+        match id {
+            1 => Ok(Codec::Gzip),
+            2 => Ok(Codec::Zstd),
+            3 => Ok(Codec::Xz),
+            _ => Err(anyhow::anyhow!("unable to recognize the codec {}.", id)),
+        }
+    }
+}
+
+pub fn compress(
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    codec: Codec,
+) -> Box<dyn AsyncBufRead + Send + Sync + Unpin> {
+    let mut header = MAGIC.to_vec();
+    header.push(codec.id());
+    let header = Cursor::new(header);
+
+    match codec {
+        Codec::Gzip => Box::new(header.chain(BufReader::new(GzipEncoder::new(reader)))),
+        Codec::Zstd => Box::new(header.chain(BufReader::new(ZstdEncoder::new(reader)))),
+        Codec::Xz => Box::new(header.chain(BufReader::new(XzEncoder::new(reader)))),
+    }
+}
+
+pub async fn decompress(
+    mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let mut header = [0u8; MAGIC.len() + 1];
+    reader.read_exact(&mut header).await?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(anyhow::anyhow!(
+            "unable to recognize the compression header."
+        ));
+    }
+
+    let reader: Box<dyn AsyncBufRead + Send + Sync + Unpin> =
+        match Codec::from_id(header[MAGIC.len()])? {
+            Codec::Gzip => Box::new(BufReader::new(GzipDecoder::new(reader))),
+            Codec::Zstd => Box::new(BufReader::new(ZstdDecoder::new(reader))),
+            Codec::Xz => Box::new(BufReader::new(XzDecoder::new(reader))),
+        };
+
+    Ok(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Codec;
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+
+    #[tokio::test]
+    async fn compress_decompress() -> anyhow::Result<()> {
+        struct TestCase {
+            codec: Codec,
+            data: Vec<u8>,
+        }
+
+        let testcases = [
+            TestCase {
+                codec: Codec::Gzip,
+                data: b"abcd".repeat(64 * 1024),
+            },
+            TestCase {
+                codec: Codec::Zstd,
+                data: b"abcd".repeat(64 * 1024),
+            },
+            TestCase {
+                codec: Codec::Xz,
+                data: b"abcd".repeat(64 * 1024),
+            },
+            TestCase {
+                codec: Codec::Zstd,
+                data: vec![],
+            },
+        ];
+
+        for testcase in testcases {
+            let mut compressed = vec![];
+            super::compress(Box::new(Cursor::new(testcase.data.clone())), testcase.codec)
+                .read_to_end(&mut compressed)
+                .await?;
+            assert_eq!(compressed[4], testcase.codec.id());
+            if !testcase.data.is_empty() {
+                assert!(compressed.len() < testcase.data.len());
+            }
+
+            let mut decompressed = vec![];
+            super::decompress(Box::new(Cursor::new(compressed)))
+                .await?
+                .read_to_end(&mut decompressed)
+                .await?;
+            assert_eq!(decompressed, testcase.data);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn decompress_rejects() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            data: &'a [u8],
+        }
+
+        let testcases = [
+            TestCase { data: b"HBZ" },
+            TestCase { data: b"abcde" },
+            TestCase {
+                data: b"HBZ\x01\x09",
+            },
+        ];
+
+        for testcase in testcases {
+            let result = super::decompress(Box::new(Cursor::new(testcase.data.to_vec()))).await;
+            assert!(result.is_err());
+        }
+
+        Ok(())
+    }
+}
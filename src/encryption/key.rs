@@ -0,0 +1,77 @@
+use argon2::Argon2;
+use std::io;
+
+pub const KEY_LEN: usize = 32;
+
+#[derive(Clone)]
+pub enum Key {
+    Raw([u8; KEY_LEN]),
+    Passphrase(String),
+}
+
+impl Key {
+    pub fn raw(key: [u8; KEY_LEN]) -> Key {
+        Key::Raw(key)
+    }
+
+    pub fn passphrase(passphrase: &str) -> Key {
+        Key::Passphrase(String::from(passphrase))
+    }
+
+    pub fn derive(&self, salt: &[u8]) -> io::Result<[u8; KEY_LEN]> {
+        match self {
+            Key::Raw(key) => Ok(*key),
+            Key::Passphrase(passphrase) => {
+                let mut key = [0u8; KEY_LEN];
+                match Argon2::default().hash_password_into(passphrase.as_bytes(), salt, &mut key) {
+                    Ok(_) => Ok(key),
+                    Err(e) => Err(io::Error::new(io::ErrorKind::InvalidInput, e.to_string())),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Key;
+
+    #[test]
+    fn key_derive() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            key: Key,
+            salt: &'a [u8],
+            same_as: Key,
+            same: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                key: Key::raw([1u8; 32]),
+                salt: b"0123456789abcdef",
+                same_as: Key::raw([1u8; 32]),
+                same: true,
+            },
+            TestCase {
+                key: Key::passphrase("secret"),
+                salt: b"0123456789abcdef",
+                same_as: Key::passphrase("secret"),
+                same: true,
+            },
+            TestCase {
+                key: Key::passphrase("secret"),
+                salt: b"0123456789abcdef",
+                same_as: Key::passphrase("secret!"),
+                same: false,
+            },
+        ];
+
+        for testcase in testcases {
+            let key = testcase.key.derive(testcase.salt)?;
+            let other = testcase.same_as.derive(testcase.salt)?;
+            assert_eq!(key == other, testcase.same);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+mod key;
+pub use key::*;
+mod stream;
+pub use stream::*;
+
+use crate::{Download, Service, Upload};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use std::marker::PhantomData;
+
+pub struct Encrypted<S> {
+    _service: PhantomData<S>,
+}
+
+pub struct DownloadSetting<T> {
+    pub inner: T,
+    pub key: Key,
+}
+
+pub struct UploadSetting<T> {
+    pub inner: T,
+    pub key: Key,
+}
+
+impl<S: Service> Service for Encrypted<S> {
+    type AuthToken = S::AuthToken;
+    type File = S::File;
+}
+
+#[async_trait]
+impl<S> Download for Encrypted<S>
+where
+    S: Download,
+    S::File: Send,
+    S::DownloadSetting: Send,
+    S::AuthToken: Sync,
+{
+    type DownloadSetting = DownloadSetting<S::DownloadSetting>;
+
+    async fn download<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        let reader = S::download(file, setting.inner, auth_token).await?;
+        Ok(decrypt(reader, &setting.key))
+    }
+}
+
+#[async_trait]
+impl<S> Upload for Encrypted<S>
+where
+    S: Upload,
+    S::UploadSetting: Send,
+    S::AuthToken: Sync,
+{
+    type UploadSetting = UploadSetting<S::UploadSetting>;
+
+    fn max_file_size(auth_token: &Self::AuthToken) -> usize {
+        plaintext_len(S::max_file_size(auth_token))
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let reader = encrypt(reader, &setting.key)?;
+        let len = len.map(encrypted_len);
+        S::upload(name, reader, len, setting.inner, auth_token).await
+    }
+}
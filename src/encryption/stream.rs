@@ -0,0 +1,285 @@
+use super::Key;
+use crate::utils::{Transform, TransformReader};
+use chacha20poly1305::aead::stream::{DecryptorBE32, EncryptorBE32};
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit};
+use futures::io::AsyncBufRead;
+use rand::RngCore;
+use std::io;
+
+const MAGIC: &[u8; 4] = b"HBE\x01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 7;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+const CHUNK_LEN: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+
+pub fn encrypted_len(len: usize) -> usize {
+    let chunks = if len == 0 { 1 } else { len.div_ceil(CHUNK_LEN) };
+    HEADER_LEN + len + chunks * TAG_LEN
+}
+
+pub fn plaintext_len(len: usize) -> usize {
+    let chunks = len.saturating_sub(HEADER_LEN).div_ceil(CHUNK_LEN + TAG_LEN);
+    len.saturating_sub(HEADER_LEN + std::cmp::max(chunks, 1) * TAG_LEN)
+}
+
+pub fn encrypt(
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    key: &Key,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let key = key.derive(&salt)?;
+    let cipher = ChaCha20Poly1305::new(&key.into());
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let encryptor = Encryptor {
+        header: Some(header),
+        stream: Some(EncryptorBE32::from_aead(cipher, nonce.as_ref().into())),
+        buffer: vec![],
+    };
+
+    Ok(Box::new(TransformReader::new(reader, encryptor)))
+}
+
+pub fn decrypt(
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    key: &Key,
+) -> Box<dyn AsyncBufRead + Send + Sync + Unpin> {
+    let decryptor = Decryptor {
+        key: key.clone(),
+        stream: None,
+        buffer: vec![],
+    };
+
+    Box::new(TransformReader::new(reader, decryptor))
+}
+
+struct Encryptor {
+    header: Option<Vec<u8>>,
+    stream: Option<EncryptorBE32<ChaCha20Poly1305>>,
+    buffer: Vec<u8>,
+}
+
+impl Encryptor {
+    fn stream(&mut self) -> io::Result<&mut EncryptorBE32<ChaCha20Poly1305>> {
+        match self.stream.as_mut() {
+            Some(v) => Ok(v),
+            None => Err(io::Error::other("stream is already finished.")),
+        }
+    }
+}
+
+impl Transform for Encryptor {
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        if let Some(header) = self.header.take() {
+            output.extend_from_slice(header.as_slice());
+        }
+
+        self.buffer.extend_from_slice(input);
+        while self.buffer.len() > CHUNK_LEN {
+            let chunk: Vec<u8> = self.buffer.drain(..CHUNK_LEN).collect();
+            let ciphertext = match self.stream()?.encrypt_next(chunk.as_slice()) {
+                Ok(v) => v,
+                Err(_e) => return Err(io::Error::other("unable to encrypt the stream.")),
+            };
+            output.extend_from_slice(ciphertext.as_slice());
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, output: &mut Vec<u8>) -> io::Result<()> {
+        if let Some(header) = self.header.take() {
+            output.extend_from_slice(header.as_slice());
+        }
+
+        let stream = match self.stream.take() {
+            Some(v) => v,
+            None => return Err(io::Error::other("stream is already finished.")),
+        };
+        let ciphertext = match stream.encrypt_last(self.buffer.as_slice()) {
+            Ok(v) => v,
+            Err(_e) => return Err(io::Error::other("unable to encrypt the stream.")),
+        };
+        self.buffer.clear();
+        output.extend_from_slice(ciphertext.as_slice());
+
+        Ok(())
+    }
+}
+
+struct Decryptor {
+    key: Key,
+    stream: Option<DecryptorBE32<ChaCha20Poly1305>>,
+    buffer: Vec<u8>,
+}
+
+impl Decryptor {
+    fn read_header(&mut self) -> io::Result<()> {
+        if self.stream.is_some() || self.buffer.len() < HEADER_LEN {
+            return Ok(());
+        }
+
+        let header: Vec<u8> = self.buffer.drain(..HEADER_LEN).collect();
+        if &header[..MAGIC.len()] != MAGIC {
+            return Err(decrypt_error());
+        }
+        let salt = &header[MAGIC.len()..MAGIC.len() + SALT_LEN];
+        let nonce = &header[MAGIC.len() + SALT_LEN..];
+
+        let key = self.key.derive(salt)?;
+        let cipher = ChaCha20Poly1305::new(&key.into());
+        self.stream = Some(DecryptorBE32::from_aead(cipher, nonce.into()));
+
+        Ok(())
+    }
+}
+
+impl Transform for Decryptor {
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        self.buffer.extend_from_slice(input);
+        self.read_header()?;
+
+        let stream = match self.stream.as_mut() {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        while self.buffer.len() > CHUNK_LEN + TAG_LEN {
+            let chunk: Vec<u8> = self.buffer.drain(..CHUNK_LEN + TAG_LEN).collect();
+            let plaintext = match stream.decrypt_next(chunk.as_slice()) {
+                Ok(v) => v,
+                Err(_e) => return Err(decrypt_error()),
+            };
+            output.extend_from_slice(plaintext.as_slice());
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, output: &mut Vec<u8>) -> io::Result<()> {
+        self.read_header()?;
+
+        let stream = match self.stream.take() {
+            Some(v) => v,
+            None => return Err(decrypt_error()),
+        };
+        let plaintext = match stream.decrypt_last(self.buffer.as_slice()) {
+            Ok(v) => v,
+            Err(_e) => return Err(decrypt_error()),
+        };
+        self.buffer.clear();
+        output.extend_from_slice(plaintext.as_slice());
+
+        Ok(())
+    }
+}
+
+fn decrypt_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "unable to decrypt the stream.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Key, CHUNK_LEN};
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+
+    #[tokio::test]
+    async fn encrypt_decrypt() -> anyhow::Result<()> {
+        struct TestCase {
+            len: usize,
+            key: Key,
+        }
+
+        let testcases = [
+            TestCase {
+                len: 0,
+                key: Key::raw([7u8; 32]),
+            },
+            TestCase {
+                len: 4,
+                key: Key::passphrase("secret"),
+            },
+            TestCase {
+                len: CHUNK_LEN,
+                key: Key::raw([7u8; 32]),
+            },
+            TestCase {
+                len: 3 * CHUNK_LEN + 1,
+                key: Key::raw([7u8; 32]),
+            },
+        ];
+
+        for testcase in testcases {
+            let data: Vec<u8> = (0..testcase.len).map(|v| v as u8).collect();
+
+            let mut encrypted = vec![];
+            super::encrypt(Box::new(Cursor::new(data.clone())), &testcase.key)?
+                .read_to_end(&mut encrypted)
+                .await?;
+            assert_eq!(encrypted.len(), super::encrypted_len(testcase.len));
+            assert_eq!(super::plaintext_len(encrypted.len()), testcase.len);
+
+            let mut decrypted = vec![];
+            super::decrypt(Box::new(Cursor::new(encrypted)), &testcase.key)
+                .read_to_end(&mut decrypted)
+                .await?;
+            assert_eq!(decrypted, data);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn decrypt_rejects() -> anyhow::Result<()> {
+        struct TestCase {
+            tamper: fn(&mut Vec<u8>),
+            key: Key,
+        }
+
+        let testcases = [
+            TestCase {
+                tamper: |_data| {},
+                key: Key::raw([8u8; 32]),
+            },
+            TestCase {
+                tamper: |data| data[40] ^= 1,
+                key: Key::raw([7u8; 32]),
+            },
+            TestCase {
+                tamper: |data| data.truncate(super::HEADER_LEN + CHUNK_LEN + 16),
+                key: Key::raw([7u8; 32]),
+            },
+            TestCase {
+                tamper: |data| data.truncate(10),
+                key: Key::raw([7u8; 32]),
+            },
+        ];
+
+        for testcase in testcases {
+            let data = vec![1u8; 2 * CHUNK_LEN + 1];
+
+            let mut encrypted = vec![];
+            super::encrypt(Box::new(Cursor::new(data)), &Key::raw([7u8; 32]))?
+                .read_to_end(&mut encrypted)
+                .await?;
+            (testcase.tamper)(&mut encrypted);
+
+            let mut decrypted = vec![];
+            let result = super::decrypt(Box::new(Cursor::new(encrypted)), &testcase.key)
+                .read_to_end(&mut decrypted)
+                .await;
+            assert!(result.is_err());
+        }
+
+        Ok(())
+    }
+}
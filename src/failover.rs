@@ -0,0 +1,318 @@
+use crate::utils::{into_reader, skip};
+use crate::{Download, DownloadRange};
+use futures::future::BoxFuture;
+use futures::io::AsyncBufRead;
+use futures::AsyncBufReadExt;
+use std::collections::VecDeque;
+use std::io;
+use std::marker::PhantomData;
+
+trait FailoverSource: Send {
+    fn open(
+        self: Box<Self>,
+        offset: usize,
+    ) -> BoxFuture<'static, anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>>>;
+}
+
+struct Source<S: Download> {
+    file: S::File,
+    setting: S::DownloadSetting,
+    auth_token: S::AuthToken,
+    _service: PhantomData<fn() -> S>,
+}
+
+impl<S> FailoverSource for Source<S>
+where
+    S: Download + 'static,
+    S::File: Send + 'static,
+    S::DownloadSetting: Send + 'static,
+    S::AuthToken: Send + Sync + 'static,
+{
+    fn open(
+        self: Box<Self>,
+        offset: usize,
+    ) -> BoxFuture<'static, anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>>> {
+        Box::pin(async move {
+            let source = *self;
+            let mut reader = S::download(source.file, source.setting, &source.auth_token).await?;
+            skip(&mut reader, offset).await?;
+            Ok(reader)
+        })
+    }
+}
+
+struct RangeSource<S: DownloadRange> {
+    source: Source<S>,
+}
+
+impl<S> FailoverSource for RangeSource<S>
+where
+    S: DownloadRange + 'static,
+    S::File: Send + 'static,
+    S::DownloadSetting: Send + 'static,
+    S::AuthToken: Send + Sync + 'static,
+{
+    fn open(
+        self: Box<Self>,
+        offset: usize,
+    ) -> BoxFuture<'static, anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>>> {
+        Box::pin(async move {
+            let source = self.source;
+            S::download_range(source.file, source.setting, &source.auth_token, offset).await
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct Failover {
+    sources: VecDeque<Box<dyn FailoverSource>>,
+}
+
+struct FailoverState {
+    sources: VecDeque<Box<dyn FailoverSource>>,
+    current: Option<Box<dyn AsyncBufRead + Send + Sync + Unpin>>,
+    offset: usize,
+    errors: Vec<String>,
+    finished: bool,
+}
+
+impl Failover {
+    pub fn new() -> Failover {
+        Failover {
+            sources: VecDeque::new(),
+        }
+    }
+
+    pub fn source<S>(
+        mut self,
+        file: S::File,
+        setting: S::DownloadSetting,
+        auth_token: S::AuthToken,
+    ) -> Failover
+    where
+        S: Download + 'static,
+        S::File: Send + 'static,
+        S::DownloadSetting: Send + 'static,
+        S::AuthToken: Send + Sync + 'static,
+    {
+        self.sources.push_back(Box::new(Source::<S> {
+            file,
+            setting,
+            auth_token,
+            _service: PhantomData,
+        }));
+        self
+    }
+
+    pub fn range_source<S>(
+        mut self,
+        file: S::File,
+        setting: S::DownloadSetting,
+        auth_token: S::AuthToken,
+    ) -> Failover
+    where
+        S: DownloadRange + 'static,
+        S::File: Send + 'static,
+        S::DownloadSetting: Send + 'static,
+        S::AuthToken: Send + Sync + 'static,
+    {
+        self.sources.push_back(Box::new(RangeSource::<S> {
+            source: Source {
+                file,
+                setting,
+                auth_token,
+                _service: PhantomData,
+            },
+        }));
+        self
+    }
+
+    pub fn download(self) -> Box<dyn AsyncBufRead + Send + Sync + Unpin> {
+        let state = FailoverState {
+            sources: self.sources,
+            current: None,
+            offset: 0,
+            errors: vec![],
+            finished: false,
+        };
+
+        into_reader(futures::stream::unfold(state, |mut state| async move {
+            if state.finished {
+                return None;
+            }
+
+            loop {
+                let reader = match state.current.as_mut() {
+                    Some(v) => v,
+                    None => {
+                        let source = match state.sources.pop_front() {
+                            Some(v) => v,
+                            None => {
+                                state.finished = true;
+                                let error = io::Error::other(format!(
+                                    "all mirrors failed: {}.",
+                                    state.errors.join("; ")
+                                ));
+                                return Some((Err(error), state));
+                            }
+                        };
+                        match source.open(state.offset).await {
+                            Ok(v) => state.current = Some(v),
+                            Err(e) => state.errors.push(e.to_string()),
+                        };
+                        continue;
+                    }
+                };
+
+                let chunk = match reader.fill_buf().await {
+                    Ok(v) => v.to_vec(),
+                    Err(e) => {
+                        state.errors.push(e.to_string());
+                        state.current = None;
+                        continue;
+                    }
+                };
+                if chunk.is_empty() {
+                    return None;
+                }
+                reader.consume_unpin(chunk.len());
+                state.offset += chunk.len();
+
+                return Some((Ok(chunk), state));
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Download, DownloadRange, Service};
+    use async_trait::async_trait;
+    use futures::io::AsyncBufRead;
+    use futures::{AsyncReadExt, TryStreamExt};
+
+    struct Flaky {}
+
+    #[derive(Clone)]
+    struct FlakyFile {
+        data: &'static str,
+        fail_after: Option<usize>,
+    }
+
+    impl Service for Flaky {
+        type AuthToken = ();
+        type File = FlakyFile;
+    }
+
+    #[async_trait]
+    impl Download for Flaky {
+        type DownloadSetting = ();
+
+        async fn download<'a>(
+            file: Self::File,
+            setting: Self::DownloadSetting,
+            auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+            Flaky::download_range(file, setting, auth_token, 0).await
+        }
+    }
+
+    #[async_trait]
+    impl DownloadRange for Flaky {
+        async fn download_range<'a>(
+            file: Self::File,
+            _setting: Self::DownloadSetting,
+            _auth_token: &'a Self::AuthToken,
+            offset: usize,
+        ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+            let data = file.data.as_bytes()[offset..].to_vec();
+            let chunks: Vec<std::io::Result<Vec<u8>>> = match file.fail_after {
+                Some(0) => return Err(anyhow::anyhow!("file does not exist.")),
+                Some(v) => vec![
+                    Ok(data[..v - offset].to_vec()),
+                    Err(std::io::ErrorKind::ConnectionReset.into()),
+                ],
+                None => vec![Ok(data)],
+            };
+            Ok(Box::new(futures::stream::iter(chunks).into_async_read()))
+        }
+    }
+
+    #[tokio::test]
+    async fn failover_download() -> anyhow::Result<()> {
+        struct TestCase {
+            files: Vec<FlakyFile>,
+            range: bool,
+            result: Option<&'static str>,
+        }
+
+        let testcases = [
+            TestCase {
+                files: vec![
+                    FlakyFile {
+                        data: "abcdef",
+                        fail_after: Some(0),
+                    },
+                    FlakyFile {
+                        data: "abcdef",
+                        fail_after: Some(2),
+                    },
+                    FlakyFile {
+                        data: "abcdef",
+                        fail_after: Some(4),
+                    },
+                    FlakyFile {
+                        data: "abcdef",
+                        fail_after: None,
+                    },
+                ],
+                range: true,
+                result: Some("abcdef"),
+            },
+            TestCase {
+                files: vec![
+                    FlakyFile {
+                        data: "abcdef",
+                        fail_after: Some(3),
+                    },
+                    FlakyFile {
+                        data: "abcdef",
+                        fail_after: None,
+                    },
+                ],
+                range: false,
+                result: Some("abcdef"),
+            },
+            TestCase {
+                files: vec![FlakyFile {
+                    data: "abcdef",
+                    fail_after: Some(3),
+                }],
+                range: true,
+                result: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let mut failover = super::Failover::new();
+            for file in testcase.files {
+                failover = match testcase.range {
+                    true => failover.range_source::<Flaky>(file, (), ()),
+                    false => failover.source::<Flaky>(file, (), ()),
+                };
+            }
+
+            let mut data = String::new();
+            let result = failover.download().read_to_string(&mut data).await;
+            match testcase.result {
+                Some(v) => {
+                    result?;
+                    assert_eq!(data, v);
+                }
+                None => assert!(result.is_err()),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use anyhow::{anyhow, Result};
+use sha2::Digest as _;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum HashAlgorithm {
+    Sha256,
+    Blake3,
+    Md5,
+}
+
+impl HashAlgorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Md5 => "md5",
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 => 32,
+            HashAlgorithm::Blake3 => 32,
+            HashAlgorithm::Md5 => 16,
+        }
+    }
+}
+
+impl TryFrom<&str> for HashAlgorithm {
+    type Error = anyhow::Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        match src {
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            "md5" => Ok(HashAlgorithm::Md5),
+            _ => Err(anyhow!("unable to recognize the algorithm {}.", src)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Digest {
+    algorithm: HashAlgorithm,
+    bytes: Vec<u8>,
+}
+
+impl Digest {
+    pub fn from_hex(algorithm: HashAlgorithm, src: &str) -> Result<Digest> {
+        if src.len() != algorithm.len() * 2 || !src.is_ascii() {
+            return Err(anyhow!(
+                "unable to recognize the {} digest.",
+                algorithm.name()
+            ));
+        }
+
+        let mut bytes = Vec::with_capacity(algorithm.len());
+        for i in (0..src.len()).step_by(2) {
+            match u8::from_str_radix(&src[i..i + 2], 16) {
+                Ok(v) => bytes.push(v),
+                Err(_e) => {
+                    return Err(anyhow!(
+                        "unable to recognize the {} digest.",
+                        algorithm.name()
+                    ))
+                }
+            }
+        }
+
+        Ok(Digest { algorithm, bytes })
+    }
+
+    pub fn get_algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    pub fn get_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.bytes.iter().map(|v| format!("{:02x}", v)).collect()
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm.name(), self.to_hex())
+    }
+}
+
+impl TryFrom<&str> for Digest {
+    type Error = anyhow::Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        match src.split_once(':') {
+            Some((algorithm, hex)) => Digest::from_hex(HashAlgorithm::try_from(algorithm)?, hex),
+            None => Err(anyhow!("unable to recognize the digest.")),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum Hasher {
+    Sha256(sha2::Sha256),
+    Blake3(Box<blake3::Hasher>),
+    Md5(md5::Md5),
+}
+
+impl Hasher {
+    pub fn new(algorithm: HashAlgorithm) -> Hasher {
+        match algorithm {
+            HashAlgorithm::Sha256 => Hasher::Sha256(sha2::Sha256::new()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgorithm::Md5 => Hasher::Md5(md5::Md5::new()),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(v) => v.update(data),
+            Hasher::Blake3(v) => {
+                v.update(data);
+            }
+            Hasher::Md5(v) => v.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> Digest {
+        match self {
+            Hasher::Sha256(v) => Digest {
+                algorithm: HashAlgorithm::Sha256,
+                bytes: v.finalize().to_vec(),
+            },
+            Hasher::Blake3(v) => Digest {
+                algorithm: HashAlgorithm::Blake3,
+                bytes: v.finalize().as_bytes().to_vec(),
+            },
+            Hasher::Md5(v) => Digest {
+                algorithm: HashAlgorithm::Md5,
+                bytes: v.finalize().to_vec(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Digest, HashAlgorithm, Hasher};
+
+    #[test]
+    fn hasher_finalize() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            algorithm: HashAlgorithm,
+            data: &'a str,
+            hex: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                algorithm: HashAlgorithm::Sha256,
+                data: "abcd",
+                hex: "88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589",
+            },
+            TestCase {
+                algorithm: HashAlgorithm::Md5,
+                data: "abcd",
+                hex: "e2fc714c4727ee9395f324cd2e7f331f",
+            },
+            TestCase {
+                algorithm: HashAlgorithm::Blake3,
+                data: "",
+                hex: "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+            },
+        ];
+
+        for testcase in testcases {
+            let mut hasher = Hasher::new(testcase.algorithm);
+            hasher.update(testcase.data.as_bytes());
+            let digest = hasher.finalize();
+            assert_eq!(digest.to_hex(), testcase.hex);
+            assert_eq!(digest, Digest::from_hex(testcase.algorithm, testcase.hex)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn digest_tryfrom_str() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            digest: Option<Digest>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "md5:e2fc714c4727ee9395f324cd2e7f331f",
+                digest: Some(Digest::from_hex(
+                    HashAlgorithm::Md5,
+                    "e2fc714c4727ee9395f324cd2e7f331f",
+                )?),
+            },
+            TestCase {
+                src: "md5:e2fc714c4727ee9395f324cd2e7f33",
+                digest: None,
+            },
+            TestCase {
+                src: "sha1:e2fc714c4727ee9395f324cd2e7f331f",
+                digest: None,
+            },
+            TestCase {
+                src: "e2fc714c4727ee9395f324cd2e7f331f",
+                digest: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let digest = Digest::try_from(testcase.src);
+            match testcase.digest {
+                Some(v) => {
+                    let digest = digest?;
+                    assert_eq!(digest.to_string(), testcase.src);
+                    assert_eq!(digest, v);
+                }
+                None => assert!(digest.is_err()),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+mod digest;
+pub use digest::*;
+mod stream;
+pub use stream::*;
+
+use crate::{Download, Service, Upload};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use std::marker::PhantomData;
+
+pub struct Hashed<S> {
+    _service: PhantomData<S>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HashedFile<F> {
+    pub file: F,
+    pub digest: Digest,
+    pub len: usize,
+}
+
+pub struct UploadSetting<T> {
+    pub inner: T,
+    pub algorithm: HashAlgorithm,
+}
+
+impl<S: Service> Service for Hashed<S> {
+    type AuthToken = S::AuthToken;
+    type File = HashedFile<S::File>;
+}
+
+#[async_trait]
+impl<S> Download for Hashed<S>
+where
+    S: Download,
+    S::File: Send,
+    S::DownloadSetting: Send,
+    S::AuthToken: Sync,
+{
+    type DownloadSetting = S::DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        let reader = S::download(file.file, setting, auth_token).await?;
+        Ok(verify(reader, file.digest))
+    }
+}
+
+#[async_trait]
+impl<S> Upload for Hashed<S>
+where
+    S: Upload,
+    S::UploadSetting: Send,
+    S::AuthToken: Sync,
+{
+    type UploadSetting = UploadSetting<S::UploadSetting>;
+
+    fn max_file_size(auth_token: &Self::AuthToken) -> usize {
+        S::max_file_size(auth_token)
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let (reader, handle) = hash(reader, setting.algorithm);
+        let file = S::upload(name, reader, len, setting.inner, auth_token).await?;
+
+        Ok(HashedFile {
+            file,
+            digest: handle.digest(),
+            len: handle.len(),
+        })
+    }
+}
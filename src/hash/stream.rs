@@ -0,0 +1,219 @@
+use super::{Digest, HashAlgorithm, Hasher};
+use crate::utils::{Transform, TransformReader};
+use futures::io::AsyncBufRead;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct IntegrityError {
+    pub expected: Digest,
+    pub actual: Digest,
+}
+
+impl IntegrityError {
+    pub fn from_io(error: &io::Error) -> Option<&IntegrityError> {
+        error.get_ref()?.downcast_ref::<IntegrityError>()
+    }
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "digest do not match, expected {} but got {}.",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+struct HashState {
+    hasher: Hasher,
+    len: usize,
+}
+
+#[derive(Clone)]
+pub struct DigestHandle {
+    state: Arc<Mutex<HashState>>,
+}
+
+impl DigestHandle {
+    pub fn digest(&self) -> Digest {
+        let state = self.state.lock().unwrap();
+        state.hasher.clone().finalize()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub fn hash(
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    algorithm: HashAlgorithm,
+) -> (Box<dyn AsyncBufRead + Send + Sync + Unpin>, DigestHandle) {
+    let handle = DigestHandle {
+        state: Arc::new(Mutex::new(HashState {
+            hasher: Hasher::new(algorithm),
+            len: 0,
+        })),
+    };
+    let hashing = Hashing {
+        handle: handle.clone(),
+    };
+
+    (Box::new(TransformReader::new(reader, hashing)), handle)
+}
+
+pub fn verify(
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    expected: Digest,
+) -> Box<dyn AsyncBufRead + Send + Sync + Unpin> {
+    let verifying = Verifying {
+        hasher: Some(Hasher::new(expected.get_algorithm())),
+        expected,
+    };
+
+    Box::new(TransformReader::new(reader, verifying))
+}
+
+struct Hashing {
+    handle: DigestHandle,
+}
+
+impl Transform for Hashing {
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        {
+            let mut state = self.handle.state.lock().unwrap();
+            state.hasher.update(input);
+            state.len += input.len();
+        }
+        output.extend_from_slice(input);
+        Ok(())
+    }
+
+    fn finish(&mut self, _output: &mut Vec<u8>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Verifying {
+    hasher: Option<Hasher>,
+    expected: Digest,
+}
+
+impl Transform for Verifying {
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(input);
+        }
+        output.extend_from_slice(input);
+        Ok(())
+    }
+
+    fn finish(&mut self, _output: &mut Vec<u8>) -> io::Result<()> {
+        let actual = match self.hasher.take() {
+            Some(v) => v.finalize(),
+            None => return Ok(()),
+        };
+        if actual != self.expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                IntegrityError {
+                    expected: self.expected.clone(),
+                    actual,
+                },
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Digest, HashAlgorithm, IntegrityError};
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+
+    #[tokio::test]
+    async fn hash() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            algorithm: HashAlgorithm,
+            data: &'a str,
+            digest: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                algorithm: HashAlgorithm::Sha256,
+                data: "abcd",
+                digest: "sha256:88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589",
+            },
+            TestCase {
+                algorithm: HashAlgorithm::Md5,
+                data: "abcd",
+                digest: "md5:e2fc714c4727ee9395f324cd2e7f331f",
+            },
+        ];
+
+        for testcase in testcases {
+            let (mut reader, handle) =
+                super::hash(Box::new(Cursor::new(testcase.data)), testcase.algorithm);
+            let mut data = String::new();
+            reader.read_to_string(&mut data).await?;
+
+            assert_eq!(data, testcase.data);
+            assert_eq!(handle.len(), testcase.data.len());
+            assert_eq!(handle.digest(), Digest::try_from(testcase.digest)?);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn verify() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            data: &'a str,
+            digest: &'a str,
+            status: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                data: "abcd",
+                digest: "md5:e2fc714c4727ee9395f324cd2e7f331f",
+                status: true,
+            },
+            TestCase {
+                data: "abce",
+                digest: "md5:e2fc714c4727ee9395f324cd2e7f331f",
+                status: false,
+            },
+        ];
+
+        for testcase in testcases {
+            let expected = Digest::try_from(testcase.digest)?;
+            let mut reader = super::verify(Box::new(Cursor::new(testcase.data)), expected.clone());
+            let mut data = String::new();
+            let result = reader.read_to_string(&mut data).await;
+
+            if testcase.status {
+                result?;
+                assert_eq!(data, testcase.data);
+            } else {
+                let error = result.unwrap_err();
+                let integrity_error = IntegrityError::from_io(&error).unwrap();
+                assert_eq!(integrity_error.expected, expected);
+            }
+        }
+
+        Ok(())
+    }
+}
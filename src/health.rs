@@ -0,0 +1,162 @@
+use crate::{LinkStatus, Probe};
+use futures::StreamExt;
+
+pub async fn check<S>(
+    files: Vec<S::File>,
+    auth_token: &S::AuthToken,
+    concurrency: usize,
+) -> Vec<LinkStatus>
+where
+    S: Probe,
+    S::File: Sync,
+    S::AuthToken: Sync,
+{
+    futures::stream::iter(files)
+        .map(|file| async move {
+            match S::probe(&file, auth_token).await {
+                Ok(v) => v,
+                Err(e) => LinkStatus::Unknown(e.to_string()),
+            }
+        })
+        .buffered(std::cmp::max(concurrency, 1))
+        .collect()
+        .await
+}
+
+pub async fn check_urls<S>(
+    urls: Vec<String>,
+    auth_token: &S::AuthToken,
+    concurrency: usize,
+) -> Vec<(String, LinkStatus)>
+where
+    S: Probe,
+    S::File: Sync + TryFrom<String>,
+    <S::File as TryFrom<String>>::Error: Into<anyhow::Error>,
+    S::AuthToken: Sync,
+{
+    futures::stream::iter(urls)
+        .map(|url| async move {
+            let status = match S::File::try_from(url.clone()) {
+                Ok(file) => match S::probe(&file, auth_token).await {
+                    Ok(v) => v,
+                    Err(e) => LinkStatus::Unknown(e.to_string()),
+                },
+                Err(e) => LinkStatus::Unknown(e.into().to_string()),
+            };
+            (url, status)
+        })
+        .buffered(std::cmp::max(concurrency, 1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FileMetadata, LinkStatus, Probe, Service};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Probes {
+        current: AtomicUsize,
+        max: AtomicUsize,
+    }
+
+    struct Fake {}
+
+    struct FakeFile(String);
+
+    impl TryFrom<String> for FakeFile {
+        type Error = anyhow::Error;
+
+        fn try_from(src: String) -> anyhow::Result<Self> {
+            match src.strip_prefix("fake://") {
+                Some(v) => Ok(FakeFile(v.to_string())),
+                None => Err(anyhow::anyhow!("unable to recognize the id.")),
+            }
+        }
+    }
+
+    impl Service for Fake {
+        type AuthToken = Probes;
+        type File = FakeFile;
+    }
+
+    #[async_trait]
+    impl Probe for Fake {
+        async fn probe<'a>(
+            file: &'a Self::File,
+            auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<LinkStatus> {
+            let current = auth_token.current.fetch_add(1, Ordering::SeqCst) + 1;
+            auth_token.max.fetch_max(current, Ordering::SeqCst);
+            async_std::task::sleep(Duration::from_millis(10)).await;
+            auth_token.current.fetch_sub(1, Ordering::SeqCst);
+
+            match file.0.as_str() {
+                "removed" => Ok(LinkStatus::Removed),
+                "private" => Ok(LinkStatus::Private),
+                "error" => Err(anyhow::anyhow!("connection reset.")),
+                name => Ok(LinkStatus::Alive(FileMetadata {
+                    name: name.to_string(),
+                    len: Some(name.len()),
+                })),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn check_urls() -> anyhow::Result<()> {
+        struct TestCase {
+            url: &'static str,
+            status: LinkStatus,
+        }
+
+        let testcases = [
+            TestCase {
+                url: "fake://a.txt",
+                status: LinkStatus::Alive(FileMetadata {
+                    name: String::from("a.txt"),
+                    len: Some(5),
+                }),
+            },
+            TestCase {
+                url: "fake://removed",
+                status: LinkStatus::Removed,
+            },
+            TestCase {
+                url: "fake://private",
+                status: LinkStatus::Private,
+            },
+            TestCase {
+                url: "fake://error",
+                status: LinkStatus::Unknown(String::from("connection reset.")),
+            },
+            TestCase {
+                url: "https://example.com/a.txt",
+                status: LinkStatus::Unknown(String::from("unable to recognize the id.")),
+            },
+            TestCase {
+                url: "fake://b.bin",
+                status: LinkStatus::Alive(FileMetadata {
+                    name: String::from("b.bin"),
+                    len: Some(5),
+                }),
+            },
+        ];
+
+        let probes = Probes::default();
+        let urls = testcases.iter().map(|v| v.url.to_string()).collect();
+        let results = super::check_urls::<Fake>(urls, &probes, 2).await;
+
+        assert!(probes.max.load(Ordering::SeqCst) <= 2);
+        assert_eq!(results.len(), testcases.len());
+        for (testcase, (url, status)) in testcases.into_iter().zip(results) {
+            assert_eq!(url, testcase.url);
+            assert_eq!(status, testcase.status);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{Download, LinkStatus, Probe};
+use futures::AsyncBufReadExt;
+use rand::Rng;
+use std::time::Duration;
+
+pub struct KeepAlive<S: Download> {
+    files: Vec<S::File>,
+    setting: S::DownloadSetting,
+    auth_token: S::AuthToken,
+    interval: Duration,
+    jitter: Duration,
+}
+
+impl<S> KeepAlive<S>
+where
+    S: Download + Probe,
+    S::File: Clone + Send + Sync,
+    S::DownloadSetting: Clone + Send,
+    S::AuthToken: Sync,
+{
+    pub fn new(
+        setting: S::DownloadSetting,
+        auth_token: S::AuthToken,
+        interval: Duration,
+        jitter: Duration,
+    ) -> KeepAlive<S> {
+        KeepAlive {
+            files: vec![],
+            setting,
+            auth_token,
+            interval,
+            jitter,
+        }
+    }
+
+    pub fn track(mut self, file: S::File) -> KeepAlive<S> {
+        self.files.push(file);
+        self
+    }
+
+    pub fn files(&self) -> &[S::File] {
+        self.files.as_slice()
+    }
+
+    pub async fn touch(
+        file: &S::File,
+        setting: S::DownloadSetting,
+        auth_token: &S::AuthToken,
+    ) -> LinkStatus {
+        let status = match S::probe(file, auth_token).await {
+            Ok(v) => v,
+            Err(e) => return LinkStatus::Unknown(e.to_string()),
+        };
+        if let LinkStatus::Alive(_) = status {
+            let result = async {
+                let mut reader = S::download(file.clone(), setting, auth_token).await?;
+                reader.fill_buf().await?;
+                anyhow::Ok(())
+            }
+            .await;
+            if let Err(e) = result {
+                return LinkStatus::Unknown(e.to_string());
+            }
+        }
+
+        status
+    }
+
+    pub async fn touch_all(&mut self) -> Vec<(S::File, LinkStatus)> {
+        let mut results = vec![];
+        for file in self.files.iter() {
+            let status = Self::touch(file, self.setting.clone(), &self.auth_token).await;
+            results.push((file.clone(), status));
+        }
+        self.files = results
+            .iter()
+            .filter(|(_, status)| *status != LinkStatus::Removed)
+            .map(|(file, _)| file.clone())
+            .collect();
+
+        results
+    }
+
+    pub async fn run<F>(mut self, mut report: F)
+    where
+        F: FnMut(&S::File, &LinkStatus),
+    {
+        while !self.files.is_empty() {
+            async_std::task::sleep(self.next_delay()).await;
+            for (file, status) in self.touch_all().await {
+                report(&file, &status);
+            }
+        }
+    }
+
+    fn next_delay(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return self.interval;
+        }
+        let jitter = rand::thread_rng().gen_range(0..=self.jitter.as_millis() as u64);
+        self.interval + Duration::from_millis(jitter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeepAlive;
+    use crate::{Download, FileMetadata, LinkStatus, Probe, Service};
+    use async_std::io::Cursor;
+    use async_trait::async_trait;
+    use futures::io::AsyncBufRead;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    struct Expiring {}
+
+    #[derive(Default)]
+    struct Touches {
+        downloads: Mutex<HashMap<&'static str, usize>>,
+    }
+
+    #[derive(Clone)]
+    struct ExpiringFile {
+        name: &'static str,
+        lifetime: usize,
+    }
+
+    impl Service for Expiring {
+        type AuthToken = Arc<Touches>;
+        type File = ExpiringFile;
+    }
+
+    #[async_trait]
+    impl Download for Expiring {
+        type DownloadSetting = ();
+
+        async fn download<'a>(
+            file: Self::File,
+            _setting: Self::DownloadSetting,
+            auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+            *auth_token
+                .downloads
+                .lock()
+                .unwrap()
+                .entry(file.name)
+                .or_insert(0) += 1;
+            Ok(Box::new(Cursor::new(file.name)))
+        }
+    }
+
+    #[async_trait]
+    impl Probe for Expiring {
+        async fn probe<'a>(
+            file: &'a Self::File,
+            auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<LinkStatus> {
+            let downloads = *auth_token
+                .downloads
+                .lock()
+                .unwrap()
+                .get(file.name)
+                .unwrap_or(&0);
+            if downloads >= file.lifetime {
+                return Ok(LinkStatus::Removed);
+            }
+            Ok(LinkStatus::Alive(FileMetadata {
+                name: file.name.to_string(),
+                len: Some(file.name.len()),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn keepalive_run() -> anyhow::Result<()> {
+        struct TestCase {
+            name: &'static str,
+            lifetime: usize,
+            downloads: usize,
+            removed: usize,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "a",
+                lifetime: 1,
+                downloads: 1,
+                removed: 1,
+            },
+            TestCase {
+                name: "b",
+                lifetime: 3,
+                downloads: 3,
+                removed: 1,
+            },
+            TestCase {
+                name: "c",
+                lifetime: 0,
+                downloads: 0,
+                removed: 1,
+            },
+        ];
+
+        let touches = Arc::new(Touches::default());
+        let mut keepalive = KeepAlive::<Expiring>::new(
+            (),
+            touches.clone(),
+            Duration::from_millis(1),
+            Duration::from_millis(2),
+        );
+        for testcase in testcases.iter() {
+            keepalive = keepalive.track(ExpiringFile {
+                name: testcase.name,
+                lifetime: testcase.lifetime,
+            });
+        }
+
+        let mut removed = HashMap::new();
+        keepalive
+            .run(|file, status| {
+                if *status == LinkStatus::Removed {
+                    *removed.entry(file.name).or_insert(0) += 1;
+                }
+            })
+            .await;
+
+        let downloads = touches.downloads.lock().unwrap();
+        for testcase in testcases {
+            assert_eq!(removed.get(testcase.name), Some(&testcase.removed));
+            assert_eq!(
+                *downloads.get(testcase.name).unwrap_or(&0),
+                testcase.downloads
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,376 @@
+use crate::hash::{Digest, HashAlgorithm, Hasher};
+use crate::Upload;
+use async_std::fs::File as AsyncFile;
+use futures::io::BufReader as AsyncBufReader;
+use futures::AsyncBufReadExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub path: PathBuf,
+    pub digest: String,
+    pub len: usize,
+    pub service: String,
+    pub file: String,
+    pub private: bool,
+    pub timestamp: u64,
+}
+
+pub struct Ledger {
+    file: File,
+    entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Ledger> {
+        let path = path.as_ref();
+        let mut entries = vec![];
+        let mut valid = 0;
+        let mut torn = false;
+        if path.exists() {
+            let mut reader = BufReader::new(File::open(path)?);
+            let mut number = 0;
+            loop {
+                let mut line = String::new();
+                let len = reader.read_line(&mut line)?;
+                if len == 0 {
+                    break;
+                }
+                number += 1;
+                // An unterminated final line is an interrupted insert and is dropped.
+                if !line.ends_with('\n') {
+                    torn = true;
+                    break;
+                }
+                if !line.trim().is_empty() {
+                    match serde_json::from_str(line.as_str()) {
+                        Ok(v) => entries.push(v),
+                        Err(e) => {
+                            return Err(anyhow::anyhow!(
+                                "ledger line {} is corrupted: {}.",
+                                number,
+                                e
+                            ))
+                        }
+                    }
+                }
+                valid += len as u64;
+            }
+        }
+
+        if torn {
+            let file = OpenOptions::new().write(true).open(path)?;
+            file.set_len(valid)?;
+            file.sync_all()?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Ledger { file, entries })
+    }
+
+    pub fn insert(&mut self, entry: LedgerEntry) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[LedgerEntry] {
+        self.entries.as_slice()
+    }
+
+    pub fn find_by_digest(&self, digest: &Digest) -> Vec<&LedgerEntry> {
+        let digest = digest.to_string();
+        self.entries.iter().filter(|v| v.digest == digest).collect()
+    }
+
+    pub fn find_by_path<P: AsRef<Path>>(&self, path: P) -> Vec<&LedgerEntry> {
+        let path = path.as_ref();
+        self.entries.iter().filter(|v| v.path == path).collect()
+    }
+
+    pub fn export_json<W: Write>(&self, writer: W) -> anyhow::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.entries)?;
+        Ok(())
+    }
+
+    pub fn export_csv<W: Write>(&self, writer: W) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        for entry in self.entries.iter() {
+            writer.serialize(entry)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+pub async fn upload<S>(
+    ledger: &mut Ledger,
+    path: &Path,
+    name: &str,
+    setting: S::UploadSetting,
+    auth_token: &S::AuthToken,
+    private: bool,
+) -> anyhow::Result<S::File>
+where
+    S: Upload,
+    S::File: Clone + Into<String> + TryFrom<String>,
+    <S::File as TryFrom<String>>::Error: Into<anyhow::Error>,
+{
+    let service = std::any::type_name::<S>();
+    let (digest, len) = hash_file(path, HashAlgorithm::Sha256).await?;
+    let published = ledger
+        .find_by_digest(&digest)
+        .into_iter()
+        .find(|v| v.service == service && v.private == private)
+        .map(|v| v.file.clone());
+    if let Some(file) = published {
+        return S::File::try_from(file).map_err(|e| e.into());
+    }
+
+    let reader = Box::new(AsyncBufReader::new(AsyncFile::open(path).await?));
+    let file = S::upload(name, reader, Some(len), setting, auth_token).await?;
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    ledger.insert(LedgerEntry {
+        path: path.to_path_buf(),
+        digest: digest.to_string(),
+        len,
+        service: service.to_string(),
+        file: file.clone().into(),
+        private,
+        timestamp,
+    })?;
+
+    Ok(file)
+}
+
+async fn hash_file(path: &Path, algorithm: HashAlgorithm) -> anyhow::Result<(Digest, usize)> {
+    let mut reader = AsyncBufReader::new(AsyncFile::open(path).await?);
+    let mut hasher = Hasher::new(algorithm);
+    let mut len: usize = 0;
+
+    loop {
+        let data = reader.fill_buf().await?;
+        if data.is_empty() {
+            break;
+        }
+        let n = data.len();
+        hasher.update(data);
+        len += n;
+        reader.consume_unpin(n);
+    }
+
+    Ok((hasher.finalize(), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ledger, LedgerEntry};
+    use crate::hash::Digest;
+    use crate::{Service, Upload};
+    use async_trait::async_trait;
+    use futures::io::AsyncBufRead;
+    use futures::AsyncReadExt;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counter {}
+
+    impl Service for Counter {
+        type AuthToken = AtomicUsize;
+        type File = String;
+    }
+
+    #[async_trait]
+    impl Upload for Counter {
+        type UploadSetting = ();
+
+        fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+            usize::MAX
+        }
+
+        async fn upload<'a>(
+            name: &'a str,
+            mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+            _len: Option<usize>,
+            _setting: Self::UploadSetting,
+            auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Self::File> {
+            let mut data = String::new();
+            reader.read_to_string(&mut data).await?;
+            let count = auth_token.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("mem://{}/{}", count, name))
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("hearthbeat-{}-{}", name, rand::random::<u64>()))
+    }
+
+    #[tokio::test]
+    async fn ledger_upload() -> anyhow::Result<()> {
+        struct TestCase {
+            content: &'static str,
+            private: bool,
+            file: &'static str,
+        }
+
+        let testcases = [
+            TestCase {
+                content: "abcd",
+                private: false,
+                file: "mem://0/a",
+            },
+            TestCase {
+                content: "abcd",
+                private: false,
+                file: "mem://0/a",
+            },
+            TestCase {
+                content: "abcd",
+                private: true,
+                file: "mem://1/a",
+            },
+            TestCase {
+                content: "efgh",
+                private: false,
+                file: "mem://2/a",
+            },
+        ];
+
+        let ledger_path = temp_path("ledger");
+        let path = temp_path("source");
+        let counter = AtomicUsize::new(0);
+        for testcase in testcases.iter() {
+            std::fs::write(&path, testcase.content)?;
+            let mut ledger = Ledger::open(&ledger_path)?;
+            let file = super::upload::<Counter>(
+                &mut ledger,
+                path.as_path(),
+                "a",
+                (),
+                &counter,
+                testcase.private,
+            )
+            .await?;
+            assert_eq!(file, testcase.file);
+        }
+
+        let ledger = Ledger::open(&ledger_path)?;
+        assert_eq!(ledger.entries().len(), 3);
+        assert_eq!(ledger.find_by_path(&path).len(), 3);
+        let digest = Digest::try_from(
+            "sha256:88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589",
+        )?;
+        let found: Vec<&str> = ledger
+            .find_by_digest(&digest)
+            .iter()
+            .map(|v| v.file.as_str())
+            .collect();
+        assert_eq!(found, vec!["mem://0/a", "mem://1/a"]);
+
+        std::fs::remove_file(path)?;
+        std::fs::remove_file(ledger_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn ledger_torn_write() -> anyhow::Result<()> {
+        struct TestCase {
+            tail: &'static str,
+            corrupted: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                tail: "{\"path\":\"/tmp/b\",\"dig",
+                corrupted: false,
+            },
+            TestCase {
+                tail: "garbage\n",
+                corrupted: true,
+            },
+        ];
+
+        let entry = |file: &str| LedgerEntry {
+            path: PathBuf::from("/tmp/a"),
+            digest: String::from("sha256:00"),
+            len: 4,
+            service: String::from("service"),
+            file: file.to_string(),
+            private: false,
+            timestamp: 1,
+        };
+        for testcase in testcases {
+            let path = temp_path("ledger");
+            Ledger::open(&path)?.insert(entry("mem://0/a"))?;
+            {
+                let mut file = OpenOptions::new().append(true).open(&path)?;
+                file.write_all(testcase.tail.as_bytes())?;
+            }
+
+            if testcase.corrupted {
+                let mut line = serde_json::to_string(&entry("mem://1/a"))?;
+                line.push('\n');
+                OpenOptions::new()
+                    .append(true)
+                    .open(&path)?
+                    .write_all(line.as_bytes())?;
+                let before = std::fs::read(&path)?;
+
+                let err = Ledger::open(&path).err().unwrap().to_string();
+                assert!(err.starts_with("ledger line 2 is corrupted"), "{}", err);
+                assert_eq!(std::fs::read(&path)?, before);
+            } else {
+                let mut ledger = Ledger::open(&path)?;
+                assert_eq!(ledger.entries().len(), 1);
+                ledger.insert(entry("mem://1/a"))?;
+
+                let ledger = Ledger::open(&path)?;
+                let files: Vec<&str> = ledger.entries().iter().map(|v| v.file.as_str()).collect();
+                assert_eq!(files, vec!["mem://0/a", "mem://1/a"]);
+            }
+
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ledger_export() -> anyhow::Result<()> {
+        let path = temp_path("ledger");
+        let mut ledger = Ledger::open(&path)?;
+        ledger.insert(LedgerEntry {
+            path: PathBuf::from("/tmp/a,b"),
+            digest: String::from("sha256:00"),
+            len: 4,
+            service: String::from("service"),
+            file: String::from("mem://0/a"),
+            private: false,
+            timestamp: 1,
+        })?;
+
+        let mut csv = vec![];
+        ledger.export_csv(&mut csv)?;
+        assert_eq!(
+            String::from_utf8(csv)?,
+            "path,digest,len,service,file,private,timestamp\n\"/tmp/a,b\",sha256:00,4,service,mem://0/a,false,1\n"
+        );
+
+        let mut json = vec![];
+        ledger.export_json(&mut json)?;
+        let entries: Vec<LedgerEntry> = serde_json::from_slice(json.as_slice())?;
+        assert_eq!(entries, ledger.entries());
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+}
@@ -1,4 +1,17 @@
+pub mod archive;
+pub mod compression;
+pub mod encryption;
+pub mod failover;
+pub mod hash;
+pub mod health;
+pub mod keepalive;
+pub mod ledger;
+pub mod mirror;
+pub mod queue;
 mod service;
 pub use service::*;
 pub mod services;
+pub mod throttle;
+pub mod transfer;
 mod utils;
+pub mod verify;
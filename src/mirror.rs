@@ -0,0 +1,227 @@
+use crate::utils::{pipe, PipeWriter};
+use crate::Upload;
+use futures::future::{join_all, BoxFuture};
+use futures::io::AsyncBufRead;
+use futures::{AsyncBufReadExt, AsyncWriteExt};
+use std::io;
+use std::marker::PhantomData;
+
+trait MirrorTarget: Send {
+    fn upload<'a>(
+        self: Box<Self>,
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+    ) -> BoxFuture<'a, anyhow::Result<String>>;
+}
+
+struct Target<S: Upload> {
+    setting: S::UploadSetting,
+    auth_token: S::AuthToken,
+    _service: PhantomData<fn() -> S>,
+}
+
+impl<S> MirrorTarget for Target<S>
+where
+    S: Upload + 'static,
+    S::File: Into<String>,
+    S::UploadSetting: Send + 'static,
+    S::AuthToken: Send + Sync + 'static,
+{
+    fn upload<'a>(
+        self: Box<Self>,
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+    ) -> BoxFuture<'a, anyhow::Result<String>> {
+        Box::pin(async move {
+            let target = *self;
+            let file = S::upload(name, reader, len, target.setting, &target.auth_token).await?;
+            Ok(file.into())
+        })
+    }
+}
+
+pub struct Mirror {
+    targets: Vec<Box<dyn MirrorTarget>>,
+    capacity: usize,
+}
+
+impl Mirror {
+    pub fn new(capacity: usize) -> Mirror {
+        Mirror {
+            targets: vec![],
+            capacity,
+        }
+    }
+
+    pub fn target<S>(mut self, setting: S::UploadSetting, auth_token: S::AuthToken) -> Mirror
+    where
+        S: Upload + 'static,
+        S::File: Into<String>,
+        S::UploadSetting: Send + 'static,
+        S::AuthToken: Send + Sync + 'static,
+    {
+        self.targets.push(Box::new(Target::<S> {
+            setting,
+            auth_token,
+            _service: PhantomData,
+        }));
+        self
+    }
+
+    pub async fn upload(
+        self,
+        name: &str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+    ) -> Vec<anyhow::Result<String>> {
+        let mut writers: Vec<Option<PipeWriter>> = vec![];
+        let mut uploads = vec![];
+        for target in self.targets {
+            let (writer, reader) = pipe(self.capacity);
+            writers.push(Some(writer));
+            uploads.push(target.upload(name, Box::new(reader), len));
+        }
+
+        let (_, files) = futures::join!(fan_out(reader, writers), join_all(uploads));
+        files
+    }
+}
+
+async fn fan_out(
+    mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    mut writers: Vec<Option<PipeWriter>>,
+) {
+    loop {
+        if writers.iter().all(|v| v.is_none()) {
+            return;
+        }
+
+        let chunk = match reader.fill_buf().await {
+            Ok(v) => v.to_vec(),
+            Err(e) => {
+                for writer in writers.into_iter().flatten() {
+                    writer.fail(io::Error::new(e.kind(), e.to_string())).await;
+                }
+                return;
+            }
+        };
+        if chunk.is_empty() {
+            break;
+        }
+        reader.consume_unpin(chunk.len());
+
+        for slot in writers.iter_mut() {
+            let failed = match slot.as_mut() {
+                Some(writer) => writer.write_all(chunk.as_slice()).await.is_err(),
+                None => false,
+            };
+            if failed {
+                *slot = None;
+            }
+        }
+    }
+
+    for writer in writers.iter_mut().flatten() {
+        let _ = writer.close().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Service, Upload};
+    use async_std::io::Cursor;
+    use async_trait::async_trait;
+    use futures::io::AsyncBufRead;
+    use futures::AsyncReadExt;
+
+    struct Echo {}
+
+    impl Service for Echo {
+        type AuthToken = ();
+        type File = String;
+    }
+
+    #[async_trait]
+    impl Upload for Echo {
+        type UploadSetting = Option<usize>;
+
+        fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+            usize::MAX
+        }
+
+        async fn upload<'a>(
+            name: &'a str,
+            mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+            _len: Option<usize>,
+            setting: Self::UploadSetting,
+            _auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Self::File> {
+            let data = match setting {
+                Some(fail_after) => {
+                    let mut data = vec![0u8; fail_after];
+                    reader.read_exact(&mut data).await?;
+                    return Err(anyhow::anyhow!("failed after {} bytes.", fail_after));
+                }
+                None => {
+                    let mut data = vec![];
+                    reader.read_to_end(&mut data).await?;
+                    data
+                }
+            };
+            Ok(format!("{}:{}", name, data.len()))
+        }
+    }
+
+    #[tokio::test]
+    async fn mirror_upload() -> anyhow::Result<()> {
+        struct TestCase {
+            settings: Vec<Option<usize>>,
+            len: usize,
+            results: Vec<Option<&'static str>>,
+        }
+
+        let testcases = [
+            TestCase {
+                settings: vec![None, None, None],
+                len: 300 * 1024,
+                results: vec![
+                    Some("name.bin:307200"),
+                    Some("name.bin:307200"),
+                    Some("name.bin:307200"),
+                ],
+            },
+            TestCase {
+                settings: vec![None, Some(1), None, Some(0)],
+                len: 300 * 1024,
+                results: vec![Some("name.bin:307200"), None, Some("name.bin:307200"), None],
+            },
+            TestCase {
+                settings: vec![None],
+                len: 0,
+                results: vec![Some("name.bin:0")],
+            },
+        ];
+
+        for testcase in testcases {
+            let mut mirror = super::Mirror::new(2);
+            for setting in testcase.settings {
+                mirror = mirror.target::<Echo>(setting, ());
+            }
+
+            let reader = Box::new(Cursor::new(vec![1u8; testcase.len]));
+            let files = mirror.upload("name.bin", reader, Some(testcase.len)).await;
+
+            assert_eq!(files.len(), testcase.results.len());
+            for (file, result) in files.into_iter().zip(testcase.results) {
+                match result {
+                    Some(v) => assert_eq!(file?, v),
+                    None => assert!(file.is_err()),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Download, Upload};
+use async_std::fs::File;
+use futures::future::BoxFuture;
+use futures::io::BufReader;
+use futures::AsyncWriteExt;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub type JobId = u64;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum JobKind {
+    Upload { path: PathBuf, name: String },
+    Download { file: String, dst: PathBuf },
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum JobStatus {
+    Queued,
+    Paused,
+    Running,
+    Completed(String),
+    Failed(String),
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Completed(_) | JobStatus::Failed(_))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: JobId,
+    pub service: String,
+    pub kind: JobKind,
+    pub priority: i32,
+    pub status: JobStatus,
+}
+
+pub type JobTask = Box<dyn FnOnce() -> BoxFuture<'static, anyhow::Result<String>> + Send>;
+
+pub fn upload_task<S>(
+    path: PathBuf,
+    name: String,
+    setting: S::UploadSetting,
+    auth_token: S::AuthToken,
+) -> JobTask
+where
+    S: Upload + 'static,
+    S::File: Into<String>,
+    S::UploadSetting: Send + 'static,
+    S::AuthToken: Send + Sync + 'static,
+{
+    Box::new(move || {
+        Box::pin(async move {
+            let file = File::open(&path).await?;
+            let len = file.metadata().await?.len() as usize;
+            let reader = Box::new(BufReader::new(file));
+            let file = S::upload(name.as_str(), reader, Some(len), setting, &auth_token).await?;
+            Ok(file.into())
+        })
+    })
+}
+
+pub fn download_task<S>(
+    file: S::File,
+    dst: PathBuf,
+    setting: S::DownloadSetting,
+    auth_token: S::AuthToken,
+) -> JobTask
+where
+    S: Download + 'static,
+    S::File: Send + 'static,
+    S::DownloadSetting: Send + 'static,
+    S::AuthToken: Send + Sync + 'static,
+{
+    Box::new(move || {
+        Box::pin(async move {
+            let reader = S::download(file, setting, &auth_token).await?;
+            let mut writer = File::create(&dst).await?;
+            futures::io::copy_buf(reader, &mut writer).await?;
+            writer.flush().await?;
+            Ok(dst.to_string_lossy().to_string())
+        })
+    })
+}
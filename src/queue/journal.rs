@@ -0,0 +1,256 @@
+use super::{JobId, JobInfo};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+trait Sink: Write + Send {
+    fn sync(&mut self) -> std::io::Result<()>;
+}
+
+impl Sink for File {
+    fn sync(&mut self) -> std::io::Result<()> {
+        self.sync_data()
+    }
+}
+
+pub struct Journal {
+    path: PathBuf,
+    sink: Box<dyn Sink>,
+    jobs: BTreeMap<JobId, JobInfo>,
+}
+
+impl Journal {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Journal> {
+        let path = path.as_ref().to_path_buf();
+        let mut jobs = BTreeMap::new();
+        let mut torn = false;
+        if path.exists() {
+            let mut reader = BufReader::new(File::open(&path)?);
+            let mut number = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 {
+                    break;
+                }
+                number += 1;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let info: JobInfo = match serde_json::from_str(line.as_str()) {
+                    Ok(v) => v,
+                    // Only the final line can be cut short by an interrupted write.
+                    Err(_) if !line.ends_with('\n') => {
+                        torn = true;
+                        break;
+                    }
+                    Err(e) => {
+                        return Err(anyhow::anyhow!(
+                            "journal line {} is corrupted: {}.",
+                            number,
+                            e
+                        ))
+                    }
+                };
+                torn = !line.ends_with('\n');
+                jobs.insert(info.id, info);
+            }
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut journal = Journal {
+            path,
+            sink: Box::new(file),
+            jobs,
+        };
+        if torn {
+            journal.compact()?;
+        }
+
+        Ok(journal)
+    }
+
+    pub fn record(&mut self, info: &JobInfo) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(info)?;
+        line.push('\n');
+        self.sink.write_all(line.as_bytes())?;
+        self.sink.sync()?;
+        self.jobs.insert(info.id, info.clone());
+        Ok(())
+    }
+
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        self.jobs.values().cloned().collect()
+    }
+
+    pub fn unfinished(&self) -> Vec<JobInfo> {
+        self.jobs
+            .values()
+            .filter(|v| !v.status.is_finished())
+            .cloned()
+            .collect()
+    }
+
+    pub fn last_id(&self) -> JobId {
+        self.jobs.keys().next_back().copied().unwrap_or(0)
+    }
+
+    pub fn compact(&mut self) -> anyhow::Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        {
+            let mut file = File::create(&tmp)?;
+            for info in self.jobs.values() {
+                let mut line = serde_json::to_string(info)?;
+                line.push('\n');
+                file.write_all(line.as_bytes())?;
+            }
+            file.sync_all()?;
+        }
+        std::fs::rename(&tmp, &self.path)?;
+
+        self.sink = Box::new(OpenOptions::new().append(true).open(&self.path)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+struct Broken;
+
+#[cfg(test)]
+impl Write for Broken {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("no space left."))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl Sink for Broken {
+    fn sync(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl Journal {
+    pub fn broken() -> Journal {
+        Journal {
+            path: PathBuf::new(),
+            sink: Box::new(Broken),
+            jobs: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Journal;
+    use crate::queue::{JobInfo, JobKind, JobStatus};
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn job(id: u64, status: JobStatus) -> JobInfo {
+        JobInfo {
+            id,
+            service: String::from("service"),
+            kind: JobKind::Upload {
+                path: PathBuf::from("/tmp/a"),
+                name: String::from("a"),
+            },
+            priority: 0,
+            status,
+        }
+    }
+
+    #[test]
+    fn journal_reopen() -> anyhow::Result<()> {
+        struct TestCase {
+            records: Vec<JobInfo>,
+            torn: bool,
+            unfinished: Vec<u64>,
+            last_id: u64,
+        }
+
+        let testcases = [
+            TestCase {
+                records: vec![],
+                torn: false,
+                unfinished: vec![],
+                last_id: 0,
+            },
+            TestCase {
+                records: vec![
+                    job(1, JobStatus::Queued),
+                    job(2, JobStatus::Queued),
+                    job(1, JobStatus::Running),
+                    job(1, JobStatus::Completed(String::from("uri"))),
+                    job(2, JobStatus::Running),
+                ],
+                torn: false,
+                unfinished: vec![2],
+                last_id: 2,
+            },
+            TestCase {
+                records: vec![job(1, JobStatus::Queued), job(3, JobStatus::Paused)],
+                torn: true,
+                unfinished: vec![1, 3],
+                last_id: 3,
+            },
+        ];
+
+        for testcase in testcases {
+            let path =
+                std::env::temp_dir().join(format!("hearthbeat-journal-{}", rand::random::<u64>()));
+            {
+                let mut journal = Journal::open(&path)?;
+                for record in testcase.records.iter() {
+                    journal.record(record)?;
+                }
+            }
+            if testcase.torn {
+                let mut file = std::fs::OpenOptions::new().append(true).open(&path)?;
+                file.write_all(b"{\"id\":4,\"serv")?;
+            }
+
+            for _ in 0..2 {
+                let mut journal = Journal::open(&path)?;
+                let unfinished: Vec<u64> = journal.unfinished().iter().map(|v| v.id).collect();
+                assert_eq!(unfinished, testcase.unfinished);
+                assert_eq!(journal.last_id(), testcase.last_id);
+                journal.compact()?;
+            }
+
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn journal_corrupted() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("hearthbeat-journal-{}", rand::random::<u64>()));
+        {
+            let mut journal = Journal::open(&path)?;
+            journal.record(&job(1, JobStatus::Queued))?;
+        }
+        {
+            let mut file = std::fs::OpenOptions::new().append(true).open(&path)?;
+            file.write_all(b"garbage\n")?;
+            let mut line = serde_json::to_string(&job(2, JobStatus::Queued))?;
+            line.push('\n');
+            file.write_all(line.as_bytes())?;
+        }
+        let before = std::fs::read(&path)?;
+
+        let err = Journal::open(&path).err().unwrap().to_string();
+        assert!(err.starts_with("journal line 2 is corrupted"), "{}", err);
+        assert_eq!(std::fs::read(&path)?, before);
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,722 @@
+mod job;
+pub use job::*;
+mod journal;
+pub use journal::*;
+
+use crate::{Download, Service, Upload};
+use async_std::channel::{unbounded, Receiver, Sender};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+struct QueueEntry {
+    info: JobInfo,
+    task: Option<JobTask>,
+}
+
+struct QueueState {
+    next_id: JobId,
+    entries: BTreeMap<JobId, QueueEntry>,
+    paused: bool,
+    limit: usize,
+    service_limits: HashMap<String, usize>,
+    running: usize,
+    running_per_service: HashMap<String, usize>,
+    journal_error: Option<String>,
+}
+
+impl QueueState {
+    fn take_startable(&mut self) -> Vec<(JobId, String, JobTask)> {
+        let mut started = vec![];
+        if self.paused {
+            return started;
+        }
+
+        let mut candidates: Vec<(i32, JobId)> = self
+            .entries
+            .values()
+            .filter(|v| v.info.status == JobStatus::Queued)
+            .map(|v| (v.info.priority, v.info.id))
+            .collect();
+        candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        for (_, id) in candidates {
+            if self.running >= self.limit {
+                break;
+            }
+
+            let entry = self.entries.get_mut(&id).unwrap();
+            let service = entry.info.service.clone();
+            let running = *self.running_per_service.get(&service).unwrap_or(&0);
+            if let Some(limit) = self.service_limits.get(&service) {
+                if running >= *limit {
+                    continue;
+                }
+            }
+
+            let task = match entry.task.take() {
+                Some(v) => v,
+                None => continue,
+            };
+            entry.info.status = JobStatus::Running;
+            self.running += 1;
+            self.running_per_service
+                .insert(service.clone(), running + 1);
+            started.push((id, service, task));
+        }
+
+        started
+    }
+
+    fn is_idle(&self) -> bool {
+        self.running == 0
+            && !self
+                .entries
+                .values()
+                .any(|v| v.info.status == JobStatus::Queued)
+    }
+}
+
+fn record(
+    state: &Mutex<QueueState>,
+    journal: &Mutex<Option<Journal>>,
+    id: JobId,
+) -> anyhow::Result<()> {
+    let mut journal = journal.lock().unwrap();
+    let journal = match journal.as_mut() {
+        Some(v) => v,
+        None => return Ok(()),
+    };
+    let info = match state.lock().unwrap().entries.get(&id) {
+        Some(v) => v.info.clone(),
+        None => return Ok(()),
+    };
+    journal.record(&info)
+}
+
+#[derive(Clone)]
+pub struct Queue {
+    state: Arc<Mutex<QueueState>>,
+    journal: Arc<Mutex<Option<Journal>>>,
+    sender: Sender<()>,
+    receiver: Receiver<()>,
+}
+
+impl Queue {
+    pub fn new(limit: usize) -> Queue {
+        let (sender, receiver) = unbounded();
+        Queue {
+            state: Arc::new(Mutex::new(QueueState {
+                next_id: 1,
+                entries: BTreeMap::new(),
+                paused: false,
+                limit,
+                service_limits: HashMap::new(),
+                running: 0,
+                running_per_service: HashMap::new(),
+                journal_error: None,
+            })),
+            journal: Arc::new(Mutex::new(None)),
+            sender,
+            receiver,
+        }
+    }
+
+    pub fn journal(self, journal: Journal) -> Queue {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.next_id = std::cmp::max(state.next_id, journal.last_id() + 1);
+        }
+        *self.journal.lock().unwrap() = Some(journal);
+        self
+    }
+
+    pub fn limit<S: Service>(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .service_limits
+            .insert(std::any::type_name::<S>().to_string(), limit);
+    }
+
+    pub fn upload<S>(
+        &self,
+        path: PathBuf,
+        name: &str,
+        setting: S::UploadSetting,
+        auth_token: S::AuthToken,
+        priority: i32,
+    ) -> anyhow::Result<JobId>
+    where
+        S: Upload + 'static,
+        S::File: Into<String>,
+        S::UploadSetting: Send + 'static,
+        S::AuthToken: Send + Sync + 'static,
+    {
+        let kind = JobKind::Upload {
+            path: path.clone(),
+            name: name.to_string(),
+        };
+        let task = upload_task::<S>(path, name.to_string(), setting, auth_token);
+        self.submit::<S>(kind, task, priority)
+    }
+
+    pub fn download<S>(
+        &self,
+        file: S::File,
+        dst: PathBuf,
+        setting: S::DownloadSetting,
+        auth_token: S::AuthToken,
+        priority: i32,
+    ) -> anyhow::Result<JobId>
+    where
+        S: Download + 'static,
+        S::File: Clone + Into<String> + Send + 'static,
+        S::DownloadSetting: Send + 'static,
+        S::AuthToken: Send + Sync + 'static,
+    {
+        let kind = JobKind::Download {
+            file: file.clone().into(),
+            dst: dst.clone(),
+        };
+        let task = download_task::<S>(file, dst, setting, auth_token);
+        self.submit::<S>(kind, task, priority)
+    }
+
+    pub fn recover_upload<S>(
+        &self,
+        info: JobInfo,
+        setting: S::UploadSetting,
+        auth_token: S::AuthToken,
+    ) -> anyhow::Result<JobId>
+    where
+        S: Upload + 'static,
+        S::File: Into<String>,
+        S::UploadSetting: Send + 'static,
+        S::AuthToken: Send + Sync + 'static,
+    {
+        if info.service != std::any::type_name::<S>() {
+            return Err(anyhow::anyhow!(
+                "job {} belongs to {}.",
+                info.id,
+                info.service
+            ));
+        }
+        let task = match &info.kind {
+            JobKind::Upload { path, name } => {
+                upload_task::<S>(path.clone(), name.clone(), setting, auth_token)
+            }
+            _ => return Err(anyhow::anyhow!("job {} is not an upload.", info.id)),
+        };
+        self.insert(info, task)
+    }
+
+    pub fn recover_download<S>(
+        &self,
+        info: JobInfo,
+        setting: S::DownloadSetting,
+        auth_token: S::AuthToken,
+    ) -> anyhow::Result<JobId>
+    where
+        S: Download + 'static,
+        S::File: TryFrom<String> + Send + 'static,
+        <S::File as TryFrom<String>>::Error: Into<anyhow::Error>,
+        S::DownloadSetting: Send + 'static,
+        S::AuthToken: Send + Sync + 'static,
+    {
+        if info.service != std::any::type_name::<S>() {
+            return Err(anyhow::anyhow!(
+                "job {} belongs to {}.",
+                info.id,
+                info.service
+            ));
+        }
+        let task = match &info.kind {
+            JobKind::Download { file, dst } => {
+                let file = S::File::try_from(file.clone()).map_err(|e| e.into())?;
+                download_task::<S>(file, dst.clone(), setting, auth_token)
+            }
+            _ => return Err(anyhow::anyhow!("job {} is not a download.", info.id)),
+        };
+        self.insert(info, task)
+    }
+
+    fn submit<S: Service>(
+        &self,
+        kind: JobKind,
+        task: JobTask,
+        priority: i32,
+    ) -> anyhow::Result<JobId> {
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            let id = state.next_id;
+            state.next_id += 1;
+            id
+        };
+        let info = JobInfo {
+            id,
+            service: std::any::type_name::<S>().to_string(),
+            kind,
+            priority,
+            status: JobStatus::Queued,
+        };
+
+        self.insert(info, task)
+    }
+
+    fn insert(&self, mut info: JobInfo, task: JobTask) -> anyhow::Result<JobId> {
+        let id = info.id;
+        if info.status != JobStatus::Paused {
+            info.status = JobStatus::Queued;
+        }
+        {
+            let mut state = self.state.lock().unwrap();
+            state.next_id = std::cmp::max(state.next_id, id + 1);
+            state.entries.insert(
+                id,
+                QueueEntry {
+                    info,
+                    task: Some(task),
+                },
+            );
+        }
+        if let Err(e) = record(&self.state, &self.journal, id) {
+            self.state.lock().unwrap().entries.remove(&id);
+            return Err(e);
+        }
+        self.notify();
+
+        Ok(id)
+    }
+
+    pub fn pause(&self) {
+        self.state.lock().unwrap().paused = true;
+        self.notify();
+    }
+
+    pub fn resume(&self) {
+        self.state.lock().unwrap().paused = false;
+        self.notify();
+    }
+
+    pub fn pause_job(&self, id: JobId) -> anyhow::Result<bool> {
+        self.set_status(id, JobStatus::Queued, JobStatus::Paused)
+    }
+
+    pub fn resume_job(&self, id: JobId) -> anyhow::Result<bool> {
+        self.set_status(id, JobStatus::Paused, JobStatus::Queued)
+    }
+
+    fn set_status(&self, id: JobId, from: JobStatus, to: JobStatus) -> anyhow::Result<bool> {
+        let changed = {
+            let mut state = self.state.lock().unwrap();
+            match state.entries.get_mut(&id) {
+                Some(entry) if entry.info.status == from => {
+                    entry.info.status = to;
+                    true
+                }
+                _ => false,
+            }
+        };
+        if changed {
+            if let Err(e) = record(&self.state, &self.journal, id) {
+                if let Some(entry) = self.state.lock().unwrap().entries.get_mut(&id) {
+                    entry.info.status = from;
+                }
+                return Err(e);
+            }
+        }
+        self.notify();
+
+        Ok(changed)
+    }
+
+    pub fn status(&self, id: JobId) -> Option<JobStatus> {
+        let state = self.state.lock().unwrap();
+        state.entries.get(&id).map(|v| v.info.status.clone())
+    }
+
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        let state = self.state.lock().unwrap();
+        state.entries.values().map(|v| v.info.clone()).collect()
+    }
+
+    pub async fn run(&self) -> anyhow::Result<()> {
+        loop {
+            let (started, idle) = {
+                let mut state = self.state.lock().unwrap();
+                if let Some(e) = state.journal_error.as_ref() {
+                    if state.running == 0 {
+                        return Err(anyhow::anyhow!("{}", e));
+                    }
+                }
+                let started = match state.journal_error {
+                    Some(_) => vec![],
+                    None => state.take_startable(),
+                };
+                (started, state.is_idle())
+            };
+            if idle {
+                return Ok(());
+            }
+
+            for (id, service, task) in started {
+                if let Err(e) = record(&self.state, &self.journal, id) {
+                    let mut state = self.state.lock().unwrap();
+                    state
+                        .journal_error
+                        .get_or_insert(format!("unable to record job {} ({}).", id, e));
+                }
+                let state = self.state.clone();
+                let journal = self.journal.clone();
+                let sender = self.sender.clone();
+                async_std::task::spawn(async move {
+                    let status = match task().await {
+                        Ok(v) => JobStatus::Completed(v),
+                        Err(e) => JobStatus::Failed(e.to_string()),
+                    };
+                    if let Some(entry) = state.lock().unwrap().entries.get_mut(&id) {
+                        entry.info.status = status;
+                    }
+                    let recorded = record(&state, &journal, id);
+                    {
+                        let mut state = state.lock().unwrap();
+                        if let Err(e) = recorded {
+                            state
+                                .journal_error
+                                .get_or_insert(format!("unable to record job {} ({}).", id, e));
+                        }
+                        state.running -= 1;
+                        if let Some(running) = state.running_per_service.get_mut(&service) {
+                            *running -= 1;
+                        }
+                    }
+                    let _ = sender.send(()).await;
+                });
+            }
+
+            if self.receiver.recv().await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn notify(&self) {
+        let _ = self.sender.try_send(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JobInfo, JobKind, JobStatus, Journal, Queue};
+    use crate::{Download, Service, Upload};
+    use async_std::io::Cursor;
+    use async_trait::async_trait;
+    use futures::io::AsyncBufRead;
+    use futures::AsyncReadExt;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Probe {
+        current: AtomicUsize,
+        max: AtomicUsize,
+        order: Mutex<Vec<String>>,
+    }
+
+    struct Sleepy {}
+
+    impl Service for Sleepy {
+        type AuthToken = Arc<Probe>;
+        type File = String;
+    }
+
+    #[async_trait]
+    impl Upload for Sleepy {
+        type UploadSetting = ();
+
+        fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+            usize::MAX
+        }
+
+        async fn upload<'a>(
+            name: &'a str,
+            mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+            _len: Option<usize>,
+            _setting: Self::UploadSetting,
+            auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Self::File> {
+            auth_token.order.lock().unwrap().push(name.to_string());
+            let current = auth_token.current.fetch_add(1, Ordering::SeqCst) + 1;
+            auth_token.max.fetch_max(current, Ordering::SeqCst);
+
+            async_std::task::sleep(Duration::from_millis(20)).await;
+            let mut data = String::new();
+            reader.read_to_string(&mut data).await?;
+
+            auth_token.current.fetch_sub(1, Ordering::SeqCst);
+            if name == "fail" {
+                return Err(anyhow::anyhow!("upload failed."));
+            }
+            Ok(format!("{}:{}", name, data))
+        }
+    }
+
+    struct Numbered {}
+
+    struct Number(u32);
+
+    impl TryFrom<String> for Number {
+        type Error = std::num::ParseIntError;
+
+        fn try_from(src: String) -> Result<Self, Self::Error> {
+            Ok(Number(src.parse()?))
+        }
+    }
+
+    impl Service for Numbered {
+        type AuthToken = ();
+        type File = Number;
+    }
+
+    #[async_trait]
+    impl Download for Numbered {
+        type DownloadSetting = ();
+
+        async fn download<'a>(
+            file: Self::File,
+            _setting: Self::DownloadSetting,
+            _auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+            Ok(Box::new(Cursor::new(file.0.to_string())))
+        }
+    }
+
+    fn temp_file() -> anyhow::Result<PathBuf> {
+        let path = std::env::temp_dir().join(format!("hearthbeat-queue-{}", rand::random::<u64>()));
+        std::fs::write(&path, "abcd")?;
+        Ok(path)
+    }
+
+    #[tokio::test]
+    async fn queue_run() -> anyhow::Result<()> {
+        struct TestCase {
+            limit: usize,
+            service_limit: Option<usize>,
+            jobs: Vec<(&'static str, i32)>,
+            max: usize,
+            order: Option<Vec<&'static str>>,
+        }
+
+        let testcases = [
+            TestCase {
+                limit: 2,
+                service_limit: None,
+                jobs: vec![("a", 0), ("b", 0), ("c", 0), ("d", 0), ("e", 0)],
+                max: 2,
+                order: None,
+            },
+            TestCase {
+                limit: 1,
+                service_limit: None,
+                jobs: vec![("a", 1), ("b", 5), ("c", 3), ("d", 5)],
+                max: 1,
+                order: Some(vec!["b", "d", "c", "a"]),
+            },
+            TestCase {
+                limit: 4,
+                service_limit: Some(1),
+                jobs: vec![("a", 0), ("b", 0), ("c", 0)],
+                max: 1,
+                order: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let path = temp_file()?;
+            let probe = Arc::new(Probe::default());
+            let queue = Queue::new(testcase.limit);
+            if let Some(limit) = testcase.service_limit {
+                queue.limit::<Sleepy>(limit);
+            }
+
+            let ids = testcase
+                .jobs
+                .iter()
+                .map(|(name, priority)| {
+                    queue.upload::<Sleepy>(path.clone(), name, (), probe.clone(), *priority)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            queue.run().await?;
+
+            assert!(probe.max.load(Ordering::SeqCst) <= testcase.max);
+            for (id, (name, _)) in ids.into_iter().zip(testcase.jobs) {
+                assert_eq!(
+                    queue.status(id),
+                    Some(JobStatus::Completed(format!("{}:abcd", name)))
+                );
+            }
+            if let Some(order) = testcase.order {
+                assert_eq!(*probe.order.lock().unwrap(), order);
+            }
+
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn queue_pause() -> anyhow::Result<()> {
+        let path = temp_file()?;
+        let probe = Arc::new(Probe::default());
+        let queue = Queue::new(2);
+
+        let paused = queue.upload::<Sleepy>(path.clone(), "paused", (), probe.clone(), 0)?;
+        let failed = queue.upload::<Sleepy>(path.clone(), "fail", (), probe.clone(), 0)?;
+        assert!(queue.pause_job(paused)?);
+
+        queue.pause();
+        let runner = {
+            let queue = queue.clone();
+            async_std::task::spawn(async move { queue.run().await })
+        };
+        async_std::task::sleep(Duration::from_millis(50)).await;
+        assert_eq!(queue.status(failed), Some(JobStatus::Queued));
+
+        queue.resume();
+        runner.await?;
+
+        assert_eq!(queue.status(paused), Some(JobStatus::Paused));
+        assert!(matches!(queue.status(failed), Some(JobStatus::Failed(_))));
+        assert_eq!(queue.jobs().len(), 2);
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn queue_recover() -> anyhow::Result<()> {
+        let path = temp_file()?;
+        let journal_path =
+            std::env::temp_dir().join(format!("hearthbeat-journal-{}", rand::random::<u64>()));
+        let probe = Arc::new(Probe::default());
+
+        let (done, paused) = {
+            let queue = Queue::new(2).journal(Journal::open(&journal_path)?);
+            let done = queue.upload::<Sleepy>(path.clone(), "done", (), probe.clone(), 0)?;
+            let paused = queue.upload::<Sleepy>(path.clone(), "paused", (), probe.clone(), 0)?;
+            queue.pause_job(paused)?;
+            queue.run().await?;
+            (done, paused)
+        };
+
+        let journal = Journal::open(&journal_path)?;
+        let unfinished = journal.unfinished();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].id, paused);
+
+        let queue = Queue::new(2).journal(journal);
+        for info in unfinished {
+            queue.recover_upload::<Sleepy>(info, (), probe.clone())?;
+        }
+        assert!(queue.resume_job(paused)?);
+        queue.run().await?;
+        assert_eq!(
+            queue.status(paused),
+            Some(JobStatus::Completed(String::from("paused:abcd")))
+        );
+
+        let next = queue.upload::<Sleepy>(path.clone(), "next", (), probe.clone(), 0)?;
+        assert!(next > paused && next > done);
+
+        let journal = Journal::open(&journal_path)?;
+        let unfinished: Vec<_> = journal.unfinished().iter().map(|v| v.id).collect();
+        assert_eq!(unfinished, vec![next]);
+        assert_eq!(journal.jobs().len(), 3);
+
+        std::fs::remove_file(path)?;
+        std::fs::remove_file(journal_path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn queue_recover_download() -> anyhow::Result<()> {
+        struct TestCase {
+            file: &'static str,
+            recovered: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                file: "42",
+                recovered: true,
+            },
+            TestCase {
+                file: "x",
+                recovered: false,
+            },
+        ];
+
+        for (i, testcase) in testcases.into_iter().enumerate() {
+            let dst =
+                std::env::temp_dir().join(format!("hearthbeat-download-{}", rand::random::<u64>()));
+            let info = JobInfo {
+                id: i as u64 + 1,
+                service: std::any::type_name::<Numbered>().to_string(),
+                kind: JobKind::Download {
+                    file: testcase.file.to_string(),
+                    dst: dst.clone(),
+                },
+                priority: 0,
+                status: JobStatus::Running,
+            };
+
+            let queue = Queue::new(1);
+            let id = queue.recover_download::<Numbered>(info, (), ());
+            assert_eq!(id.is_ok(), testcase.recovered);
+            if let Ok(id) = id {
+                queue.run().await?;
+                assert_eq!(
+                    queue.status(id),
+                    Some(JobStatus::Completed(dst.to_string_lossy().to_string()))
+                );
+                assert_eq!(std::fs::read_to_string(&dst)?, testcase.file);
+                std::fs::remove_file(dst)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn queue_journal_failure() -> anyhow::Result<()> {
+        let path = temp_file()?;
+        let probe = Arc::new(Probe::default());
+
+        let queue = Queue::new(1).journal(Journal::broken());
+        assert!(queue
+            .upload::<Sleepy>(path.clone(), "a", (), probe.clone(), 0)
+            .is_err());
+        assert!(queue.jobs().is_empty());
+
+        let queue = Queue::new(1);
+        let first = queue.upload::<Sleepy>(path.clone(), "a", (), probe.clone(), 0)?;
+        let second = queue.upload::<Sleepy>(path.clone(), "b", (), probe.clone(), 0)?;
+        let queue = queue.journal(Journal::broken());
+        assert!(queue.pause_job(second).is_err());
+        assert_eq!(queue.status(second), Some(JobStatus::Queued));
+
+        let err = queue.run().await.unwrap_err().to_string();
+        assert!(err.starts_with("unable to record job"), "{}", err);
+        assert_eq!(
+            queue.status(first),
+            Some(JobStatus::Completed(String::from("a:abcd")))
+        );
+        assert_eq!(queue.status(second), Some(JobStatus::Queued));
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+}
@@ -17,6 +17,58 @@ pub trait Download: Service {
     ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>>;
 }
 
+#[async_trait]
+pub trait DownloadRange: Download {
+    async fn download_range<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>>;
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FileMetadata {
+    pub name: String,
+    pub len: Option<usize>,
+}
+
+#[async_trait]
+pub trait Metadata: Service {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata>;
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LinkStatus {
+    Alive(FileMetadata),
+    Removed,
+    Private,
+    Unknown(String),
+}
+
+#[async_trait]
+pub trait Probe: Service {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus>;
+}
+
+#[async_trait]
+pub trait Delete: Service {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+pub trait List: Service {
+    async fn list<'a>(
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Vec<(Self::File, FileMetadata)>>;
+}
+
 #[async_trait]
 pub trait Upload: Service {
     type UploadSetting;
@@ -0,0 +1,63 @@
+#[derive(Debug, PartialEq, Clone)]
+pub enum Credential {
+    Anonymous,
+    Login { username: String, password: String },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Security {
+    Plain,
+    ExplicitTls,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthToken {
+    pub host: String,
+    pub port: u16,
+    pub credential: Credential,
+    pub root: String,
+    pub security: Security,
+    pub accept_invalid_certs: bool,
+}
+
+impl AuthToken {
+    pub fn new(host: &str, port: u16, credential: Credential, root: &str) -> AuthToken {
+        let root = match root.trim_end_matches('/') {
+            "" if root.starts_with('/') => "/",
+            v => v,
+        };
+        AuthToken {
+            host: host.to_string(),
+            port,
+            credential,
+            root: root.to_string(),
+            security: Security::Plain,
+            accept_invalid_certs: false,
+        }
+    }
+
+    pub fn explicit_tls(mut self) -> AuthToken {
+        self.security = Security::ExplicitTls;
+        self
+    }
+
+    pub fn danger_accept_invalid_certs(mut self) -> AuthToken {
+        self.accept_invalid_certs = true;
+        self
+    }
+
+    pub(super) fn remote_path(&self, path: &str) -> String {
+        match self.root.as_str() {
+            "" => path.to_string(),
+            "/" => format!("/{}", path),
+            root => format!("{}/{}", root, path),
+        }
+    }
+
+    pub(super) fn login(&self) -> (&str, &str) {
+        match &self.credential {
+            Credential::Anonymous => ("anonymous", "anonymous@"),
+            Credential::Login { username, password } => (username.as_str(), password.as_str()),
+        }
+    }
+}
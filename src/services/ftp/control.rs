@@ -0,0 +1,308 @@
+use super::{AuthToken, Security};
+use async_native_tls::{TlsConnector, TlsStream};
+use async_std::io::BufReader;
+use async_std::net::TcpStream;
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::{AsyncBufReadExt, AsyncWriteExt};
+use std::fmt;
+use std::io;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub enum Stream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Stream::Plain(v) => Pin::new(v).poll_read(cx, buf),
+            Stream::Tls(v) => Pin::new(v.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Stream::Plain(v) => Pin::new(v).poll_write(cx, buf),
+            Stream::Tls(v) => Pin::new(v.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Plain(v) => Pin::new(v).poll_flush(cx),
+            Stream::Tls(v) => Pin::new(v.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Plain(v) => Pin::new(v).poll_close(cx),
+            Stream::Tls(v) => Pin::new(v.as_mut()).poll_close(cx),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Reply {
+    pub code: u16,
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReplyError {
+    pub code: u16,
+    pub text: String,
+    pub command: String,
+}
+
+impl fmt::Display for ReplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unexpected reply {} {} to {}.",
+            self.code, self.text, self.command
+        )
+    }
+}
+
+impl std::error::Error for ReplyError {}
+
+pub struct Control {
+    stream: BufReader<Stream>,
+    peer: IpAddr,
+    auth_token: AuthToken,
+}
+
+async fn upgrade(stream: TcpStream, auth_token: &AuthToken) -> anyhow::Result<Stream> {
+    let connector =
+        TlsConnector::new().danger_accept_invalid_certs(auth_token.accept_invalid_certs);
+    let stream = connector.connect(auth_token.host.as_str(), stream).await?;
+    Ok(Stream::Tls(Box::new(stream)))
+}
+
+impl Control {
+    pub async fn connect(auth_token: &AuthToken) -> anyhow::Result<Control> {
+        let stream = TcpStream::connect((auth_token.host.as_str(), auth_token.port)).await?;
+        let peer = stream.peer_addr()?.ip();
+        let mut control = Control {
+            stream: BufReader::new(Stream::Plain(stream)),
+            peer,
+            auth_token: auth_token.clone(),
+        };
+        control.expect(None, &[220]).await?;
+
+        if auth_token.security == Security::ExplicitTls {
+            control.expect(Some("AUTH TLS"), &[234]).await?;
+            let Control { stream, peer, .. } = control;
+            let stream = match stream.into_inner() {
+                Stream::Plain(v) => upgrade(v, auth_token).await?,
+                v => v,
+            };
+            control = Control {
+                stream: BufReader::new(stream),
+                peer,
+                auth_token: auth_token.clone(),
+            };
+            control.expect(Some("PBSZ 0"), &[200]).await?;
+            control.expect(Some("PROT P"), &[200]).await?;
+        }
+
+        let (username, password) = auth_token.login();
+        let reply = control
+            .expect(Some(format!("USER {}", username).as_str()), &[230, 331])
+            .await?;
+        if reply.code == 331 {
+            control
+                .expect(Some(format!("PASS {}", password).as_str()), &[230, 202])
+                .await?;
+        }
+        control.expect(Some("TYPE I"), &[200]).await?;
+
+        Ok(control)
+    }
+
+    pub async fn command(&mut self, command: &str) -> anyhow::Result<Reply> {
+        let stream = self.stream.get_mut();
+        stream
+            .write_all(format!("{}\r\n", command).as_bytes())
+            .await?;
+        stream.flush().await?;
+        self.reply().await
+    }
+
+    pub async fn reply(&mut self) -> anyhow::Result<Reply> {
+        let mut text = String::new();
+        let mut code: Option<String> = None;
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await? == 0 {
+                return Err(anyhow::anyhow!("connection closed."));
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(line);
+
+            let prefix = line
+                .get(..3)
+                .filter(|v| v.bytes().all(|b| b.is_ascii_digit()));
+            let last = line.as_bytes().get(3) != Some(&b'-');
+            match (code.as_deref(), prefix) {
+                (None, Some(v)) if last => {
+                    return Ok(Reply {
+                        code: v.parse()?,
+                        text: line[3..].trim().to_string(),
+                    })
+                }
+                (None, Some(v)) => code = Some(v.to_string()),
+                (Some(c), Some(v)) if c == v && last => {
+                    return Ok(Reply {
+                        code: v.parse()?,
+                        text,
+                    })
+                }
+                (None, None) => {
+                    return Err(anyhow::anyhow!("unable to recognize the reply {}.", line))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub async fn expect(&mut self, command: Option<&str>, codes: &[u16]) -> anyhow::Result<Reply> {
+        let reply = match command {
+            Some(v) => self.command(v).await?,
+            None => self.reply().await?,
+        };
+        if !codes.contains(&reply.code) {
+            let command = command
+                .map(|v| v.split(' ').next().unwrap_or_default())
+                .unwrap_or("connect");
+            return Err(ReplyError {
+                code: reply.code,
+                text: reply.text,
+                command: command.to_string(),
+            }
+            .into());
+        }
+        Ok(reply)
+    }
+
+    pub async fn passive(&mut self) -> anyhow::Result<TcpStream> {
+        let reply = self.command("EPSV").await?;
+        let port = match reply.code {
+            229 => parse_epsv(reply.text.as_str()),
+            _ => {
+                let reply = self.expect(Some("PASV"), &[227]).await?;
+                parse_pasv(reply.text.as_str())
+            }
+        };
+        let port = match port {
+            Some(v) => v,
+            None => return Err(anyhow::anyhow!("unable to recognize the passive reply.")),
+        };
+
+        Ok(TcpStream::connect((self.peer, port)).await?)
+    }
+
+    pub async fn open_data(&self, stream: TcpStream) -> anyhow::Result<Stream> {
+        match self.auth_token.security {
+            Security::Plain => Ok(Stream::Plain(stream)),
+            Security::ExplicitTls => upgrade(stream, &self.auth_token).await,
+        }
+    }
+
+    pub async fn quit(mut self) {
+        let _ = self.command("QUIT").await;
+    }
+}
+
+fn parse_epsv(text: &str) -> Option<u16> {
+    let start = text.find('(')?;
+    let end = text[start..].find(')')? + start;
+    let inner = &text[start + 1..end];
+    let delimiter = inner.chars().next()?;
+    inner
+        .trim_matches(delimiter)
+        .rsplit(delimiter)
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn parse_pasv(text: &str) -> Option<u16> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let numbers: Vec<u16> = text[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|v| !v.is_empty())
+        .take(6)
+        .map(|v| v.parse().ok())
+        .collect::<Option<Vec<u16>>>()?;
+    if numbers.len() != 6 || numbers[4] > 255 || numbers[5] > 255 {
+        return None;
+    }
+    Some(numbers[4] * 256 + numbers[5])
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse_passive() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            problem: &'a str,
+            epsv: bool,
+            solution: Option<u16>,
+        }
+
+        let testcases = [
+            TestCase {
+                problem: "Entering Extended Passive Mode (|||6446|)",
+                epsv: true,
+                solution: Some(6446),
+            },
+            TestCase {
+                problem: "Entering Extended Passive Mode (!!!21000!)",
+                epsv: true,
+                solution: Some(21000),
+            },
+            TestCase {
+                problem: "Entering Passive Mode (192,168,1,2,19,137).",
+                epsv: false,
+                solution: Some(5001),
+            },
+            TestCase {
+                problem: "Entering Passive Mode 10,0,0,1,4,1",
+                epsv: false,
+                solution: Some(1025),
+            },
+            TestCase {
+                problem: "Entering Passive Mode (10,0,0,1,4)",
+                epsv: false,
+                solution: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let solution = match testcase.epsv {
+                true => super::parse_epsv(testcase.problem),
+                false => super::parse_pasv(testcase.problem),
+            };
+            assert_eq!(solution, testcase.solution);
+        }
+        Ok(())
+    }
+}
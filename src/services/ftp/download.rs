@@ -0,0 +1,103 @@
+use super::{AuthToken, Control, File, ReplyError};
+use crate::utils::pipe;
+use crate::{FileMetadata, LinkStatus};
+use futures::{AsyncBufRead, AsyncWriteExt};
+
+pub async fn download_file<'a>(
+    auth_token: &'a AuthToken,
+    file: &'a File,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let path = auth_token.remote_path(file.get_path());
+    let mut control = Control::connect(auth_token).await?;
+
+    let stream = control.passive().await?;
+    if offset > 0 {
+        control
+            .expect(Some(format!("REST {}", offset).as_str()), &[350])
+            .await?;
+    }
+    control
+        .expect(Some(format!("RETR {}", path).as_str()), &[125, 150])
+        .await?;
+    let mut data = control.open_data(stream).await?;
+
+    let (mut writer, reader) = pipe(16);
+    async_std::task::spawn(async move {
+        let result = async {
+            futures::io::copy(&mut data, &mut writer).await?;
+            drop(data);
+            control.expect(None, &[226, 250]).await?;
+            anyhow::Ok(())
+        }
+        .await;
+        match result {
+            Ok(_) => {
+                let _ = writer.close().await;
+            }
+            Err(e) => {
+                let e = std::io::Error::other(e.to_string());
+                writer.fail(e).await;
+            }
+        }
+        control.quit().await;
+    });
+
+    Ok(Box::new(reader))
+}
+
+pub async fn file_size<'a>(
+    control: &'a mut Control,
+    path: &'a str,
+) -> anyhow::Result<Result<usize, u16>> {
+    let reply = control.command(format!("SIZE {}", path).as_str()).await?;
+    match reply.code {
+        213 => Ok(Ok(reply.text.trim().parse()?)),
+        v => Ok(Err(v)),
+    }
+}
+
+pub async fn file_metadata<'a>(
+    auth_token: &'a AuthToken,
+    file: &'a File,
+) -> anyhow::Result<FileMetadata> {
+    let path = auth_token.remote_path(file.get_path());
+    let mut control = Control::connect(auth_token).await?;
+    let size = file_size(&mut control, path.as_str()).await?;
+    control.quit().await;
+
+    match size {
+        Ok(len) => Ok(FileMetadata {
+            name: file.get_name().to_string(),
+            len: Some(len),
+        }),
+        Err(code) => Err(anyhow::anyhow!("unexpected reply {} to SIZE.", code)),
+    }
+}
+
+pub async fn file_status<'a>(
+    auth_token: &'a AuthToken,
+    file: &'a File,
+) -> anyhow::Result<LinkStatus> {
+    let path = auth_token.remote_path(file.get_path());
+    let mut control = match Control::connect(auth_token).await {
+        Ok(v) => v,
+        Err(e) => match e.downcast_ref::<ReplyError>() {
+            Some(ReplyError { code: 530, .. }) => return Ok(LinkStatus::Private),
+            _ => return Err(e),
+        },
+    };
+    let size = file_size(&mut control, path.as_str()).await?;
+    control.quit().await;
+
+    let status = match size {
+        Ok(len) => LinkStatus::Alive(FileMetadata {
+            name: file.get_name().to_string(),
+            len: Some(len),
+        }),
+        Err(550) => LinkStatus::Removed,
+        Err(530) => LinkStatus::Private,
+        Err(v) => LinkStatus::Unknown(format!("unexpected reply {} to SIZE.", v)),
+    };
+    Ok(status)
+}
@@ -0,0 +1,114 @@
+use anyhow::{anyhow, Error, Result};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct File {
+    path: String,
+}
+
+impl File {
+    pub fn new(path: &str) -> Result<File> {
+        let valid = path
+            .split('/')
+            .all(|v| !v.is_empty() && v != "." && v != "..");
+        if !valid {
+            return Err(anyhow!("unable to recognize the path {}.", path));
+        }
+        Ok(File {
+            path: path.to_string(),
+        })
+    }
+
+    pub fn get_path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    pub fn get_name(&self) -> &str {
+        match self.path.rsplit_once('/') {
+            Some((_, name)) => name,
+            None => self.path.as_str(),
+        }
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        match src.strip_prefix("ftp://") {
+            Some(v) => File::new(v),
+            None => Err(anyhow!("unable to recognize the id.")),
+        }
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+impl From<File> for String {
+    fn from(file: File) -> String {
+        format!("ftp://{}", file.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_tryfrom_str() -> Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            path: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "ftp://a.txt",
+                path: Some("a.txt"),
+            },
+            TestCase {
+                src: "ftp://deliveries/2023/a.txt",
+                path: Some("deliveries/2023/a.txt"),
+            },
+            TestCase {
+                src: "ftp://../a.txt",
+                path: None,
+            },
+            TestCase {
+                src: "ftp:///etc/passwd",
+                path: None,
+            },
+            TestCase {
+                src: "ftp://a//b.txt",
+                path: None,
+            },
+            TestCase {
+                src: "ftp://",
+                path: None,
+            },
+            TestCase {
+                src: "local://a.txt",
+                path: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let file = File::try_from(testcase.src);
+            match testcase.path {
+                Some(v) => {
+                    let file = file?;
+                    assert_eq!(file.get_path(), v);
+                    let uri: String = file.into();
+                    assert_eq!(uri, testcase.src);
+                }
+                None => assert!(file.is_err()),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use super::{AuthToken, Control};
+use crate::FileMetadata;
+use futures::AsyncReadExt;
+
+async fn list_dir<'a>(
+    control: &'a mut Control,
+    path: &'a str,
+) -> anyhow::Result<Vec<(String, bool, Option<usize>)>> {
+    let stream = control.passive().await?;
+    control
+        .expect(Some(format!("MLSD {}", path).as_str()), &[125, 150])
+        .await?;
+    let mut data = control.open_data(stream).await?;
+    let mut listing = String::new();
+    data.read_to_string(&mut listing).await?;
+    drop(data);
+    control.expect(None, &[226, 250]).await?;
+
+    Ok(listing.lines().filter_map(parse_entry).collect())
+}
+
+fn parse_entry(line: &str) -> Option<(String, bool, Option<usize>)> {
+    let (facts, name) = line.split_once(' ')?;
+    let mut kind = None;
+    let mut len = None;
+    for fact in facts.split(';') {
+        match fact.split_once('=') {
+            Some((k, v)) if k.eq_ignore_ascii_case("type") => kind = Some(v.to_lowercase()),
+            Some((k, v)) if k.eq_ignore_ascii_case("size") => len = v.parse().ok(),
+            _ => {}
+        }
+    }
+    match kind.as_deref() {
+        Some("file") => Some((name.to_string(), false, len)),
+        Some("dir") => Some((name.to_string(), true, None)),
+        _ => None,
+    }
+}
+
+pub async fn list_files(auth_token: &AuthToken) -> anyhow::Result<Vec<(String, FileMetadata)>> {
+    let mut control = Control::connect(auth_token).await?;
+
+    let mut files = vec![];
+    let mut dirs = vec![String::new()];
+    while let Some(dir) = dirs.pop() {
+        let remote = match dir.is_empty() {
+            true if auth_token.root.is_empty() => String::from("."),
+            true => auth_token.root.clone(),
+            false => auth_token.remote_path(dir.as_str()),
+        };
+        for (name, is_dir, len) in list_dir(&mut control, remote.as_str()).await? {
+            let path = match dir.is_empty() {
+                true => name.clone(),
+                false => format!("{}/{}", dir, name),
+            };
+            match is_dir {
+                true => dirs.push(path),
+                false => files.push((path, FileMetadata { name, len })),
+            }
+        }
+    }
+    control.quit().await;
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(files)
+}
+
+pub async fn delete_file<'a>(auth_token: &'a AuthToken, path: &'a str) -> anyhow::Result<()> {
+    let mut control = Control::connect(auth_token).await?;
+    control
+        .expect(Some(format!("DELE {}", path).as_str()), &[250])
+        .await?;
+    control.quit().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse_entry() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            problem: &'a str,
+            solution: Option<(&'a str, bool, Option<usize>)>,
+        }
+
+        let testcases = [
+            TestCase {
+                problem: "type=file;size=1024;modify=20230101000000; a b.txt",
+                solution: Some(("a b.txt", false, Some(1024))),
+            },
+            TestCase {
+                problem: "Type=dir;Modify=20230101000000; incoming",
+                solution: Some(("incoming", true, None)),
+            },
+            TestCase {
+                problem: "type=cdir;modify=20230101000000; .",
+                solution: None,
+            },
+            TestCase {
+                problem: "garbage",
+                solution: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let solution = super::parse_entry(testcase.problem);
+            assert_eq!(
+                solution,
+                testcase
+                    .solution
+                    .map(|(name, dir, len)| (name.to_string(), dir, len))
+            );
+        }
+        Ok(())
+    }
+}
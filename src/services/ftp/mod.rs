@@ -0,0 +1,400 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod control;
+use control::*;
+mod download;
+use download::*;
+mod list;
+use list::*;
+mod upload;
+use upload::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Service,
+    Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+
+pub struct Ftp {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UploadSetting {
+    pub prefix: String,
+    pub resume: bool,
+}
+
+impl Service for Ftp {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for Ftp {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(auth_token, &file, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Ftp {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(auth_token, &file, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for Ftp {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        file_metadata(auth_token, file).await
+    }
+}
+
+#[async_trait]
+impl Probe for Ftp {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        file_status(auth_token, file).await
+    }
+}
+
+#[async_trait]
+impl Delete for Ftp {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        delete_file(auth_token, auth_token.remote_path(file.get_path()).as_str()).await
+    }
+}
+
+#[async_trait]
+impl List for Ftp {
+    async fn list<'a>(
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Vec<(Self::File, FileMetadata)>> {
+        let mut files = vec![];
+        for (path, metadata) in list_files(auth_token).await? {
+            files.push((File::new(path.as_str())?, metadata));
+        }
+        Ok(files)
+    }
+}
+
+#[async_trait]
+impl Upload for Ftp {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::MAX
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let file = File::new(format!("{}{}", setting.prefix, name).as_str())?;
+        upload_file(auth_token, &file, reader, len, setting.resume).await?;
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, Credential, DownloadSetting, File, Ftp, UploadSetting};
+    use crate::{
+        Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Upload,
+    };
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, Mutex};
+
+    type Store = Arc<Mutex<BTreeMap<String, Vec<u8>>>>;
+
+    fn listing(store: &Store, dir: &str) -> String {
+        let prefix = format!("{}/", dir.trim_end_matches('/'));
+        let mut dirs = BTreeSet::new();
+        let mut lines = vec![];
+        for (path, data) in store.lock().unwrap().iter() {
+            let rest = match path.strip_prefix(prefix.as_str()) {
+                Some(v) => v,
+                None => continue,
+            };
+            match rest.split_once('/') {
+                Some((name, _)) => {
+                    dirs.insert(name.to_string());
+                }
+                None => lines.push(format!("type=file;size={}; {}\r\n", data.len(), rest)),
+            }
+        }
+        for name in dirs {
+            lines.push(format!("type=dir; {}\r\n", name));
+        }
+        format!("type=cdir; .\r\n{}", lines.concat())
+    }
+
+    fn session(stream: TcpStream, store: Store) -> std::io::Result<()> {
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+        let mut passive: Option<TcpListener> = None;
+        let mut offset = 0;
+        let mut user = String::new();
+
+        writer.write_all(b"220-mock ftp\r\n220 ready\r\n")?;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            let line = line.trim_end();
+            let (command, arg) = line.split_once(' ').unwrap_or((line, ""));
+            let reply = match command {
+                "USER" => {
+                    user = arg.to_string();
+                    String::from("331 password required")
+                }
+                "PASS" if user == "user" && arg == "pass" => String::from("230 logged in"),
+                "PASS" => String::from("530 login incorrect"),
+                "TYPE" => String::from("200 binary"),
+                "MKD" => String::from("257 created"),
+                "EPSV" => {
+                    let listener = TcpListener::bind("127.0.0.1:0")?;
+                    let port = listener.local_addr()?.port();
+                    passive = Some(listener);
+                    format!("229 Entering Extended Passive Mode (|||{}|)", port)
+                }
+                "REST" => {
+                    offset = arg.parse().unwrap_or(0);
+                    format!("350 restarting at {}", offset)
+                }
+                "SIZE" => match store.lock().unwrap().get(arg) {
+                    Some(v) => format!("213 {}", v.len()),
+                    None => String::from("550 no such file"),
+                },
+                "DELE" => match store.lock().unwrap().remove(arg) {
+                    Some(_) => String::from("250 deleted"),
+                    None => String::from("550 no such file"),
+                },
+                "RETR" | "STOR" | "APPE" | "MLSD" => {
+                    let data = match command {
+                        "RETR" => store
+                            .lock()
+                            .unwrap()
+                            .get(arg)
+                            .map(|v| v[offset.min(v.len())..].to_vec()),
+                        "MLSD" => Some(listing(&store, arg).into_bytes()),
+                        _ => Some(vec![]),
+                    };
+                    let (listener, data) = match (passive.take(), data) {
+                        (Some(listener), Some(data)) => (listener, data),
+                        _ => {
+                            writer.write_all(b"550 no such file\r\n")?;
+                            continue;
+                        }
+                    };
+                    writer.write_all(b"150 opening data connection\r\n")?;
+                    let (mut stream, _) = listener.accept()?;
+                    match command {
+                        "STOR" | "APPE" => {
+                            let mut received = vec![];
+                            stream.read_to_end(&mut received)?;
+                            let mut store = store.lock().unwrap();
+                            let entry = store.entry(arg.to_string()).or_default();
+                            if command == "STOR" {
+                                entry.clear();
+                            }
+                            entry.extend(received);
+                        }
+                        _ => stream.write_all(data.as_slice())?,
+                    }
+                    drop(stream);
+                    offset = 0;
+                    String::from("226 transfer complete")
+                }
+                "QUIT" => {
+                    writer.write_all(b"221 bye\r\n")?;
+                    return Ok(());
+                }
+                _ => String::from("502 not implemented"),
+            };
+            writer.write_all(format!("{}\r\n", reply).as_bytes())?;
+        }
+    }
+
+    fn server(store: Store) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let store = store.clone();
+                std::thread::spawn(move || session(stream, store));
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn ftp_roundtrip() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            name: &'a str,
+            setting: UploadSetting,
+            existing: Option<&'a str>,
+            data: &'a str,
+            stored: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "a.txt",
+                setting: UploadSetting::default(),
+                existing: None,
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+            TestCase {
+                name: "b.bin",
+                setting: UploadSetting {
+                    resume: true,
+                    ..UploadSetting::default()
+                },
+                existing: Some("abc"),
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+            TestCase {
+                name: "c.bin",
+                setting: UploadSetting::default(),
+                existing: Some("stale data"),
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+            TestCase {
+                name: "d.txt",
+                setting: UploadSetting {
+                    prefix: String::from("in/"),
+                    resume: true,
+                },
+                existing: None,
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+            TestCase {
+                name: "e.bin",
+                setting: UploadSetting {
+                    resume: true,
+                    ..UploadSetting::default()
+                },
+                existing: Some("stale data!"),
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+        ];
+
+        let store: Store = Arc::new(Mutex::new(BTreeMap::new()));
+        let port = server(store.clone());
+        let credential = Credential::Login {
+            username: String::from("user"),
+            password: String::from("pass"),
+        };
+        let auth_token = AuthToken::new("127.0.0.1", port, credential, "/drop/");
+        for testcase in testcases {
+            let path = format!("/drop/{}{}", testcase.setting.prefix, testcase.name);
+            if let Some(v) = testcase.existing {
+                store
+                    .lock()
+                    .unwrap()
+                    .insert(path.clone(), v.as_bytes().to_vec());
+            }
+
+            let reader = Box::new(Cursor::new(testcase.data));
+            let len = Some(testcase.data.len());
+            let file =
+                Ftp::upload(testcase.name, reader, len, testcase.setting, &auth_token).await?;
+            assert_eq!(
+                store.lock().unwrap().get(&path).map(|v| v.as_slice()),
+                Some(testcase.stored.as_bytes())
+            );
+
+            let mut data = String::new();
+            Ftp::download(file.clone(), DownloadSetting {}, &auth_token)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, testcase.stored);
+
+            let mut data = String::new();
+            Ftp::download_range(file.clone(), DownloadSetting {}, &auth_token, 3)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, &testcase.stored[3..]);
+
+            let metadata = FileMetadata {
+                name: testcase.name.to_string(),
+                len: Some(testcase.stored.len()),
+            };
+            assert_eq!(Ftp::metadata(&file, &auth_token).await?, metadata);
+            assert_eq!(
+                Ftp::probe(&file, &auth_token).await?,
+                LinkStatus::Alive(metadata)
+            );
+        }
+
+        let listed: Vec<String> = Ftp::list(&auth_token)
+            .await?
+            .into_iter()
+            .map(|(file, _)| file.into())
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                "ftp://a.txt",
+                "ftp://b.bin",
+                "ftp://c.bin",
+                "ftp://e.bin",
+                "ftp://in/d.txt"
+            ]
+        );
+
+        let file = File::try_from("ftp://a.txt")?;
+        Ftp::delete(file.clone(), &auth_token).await?;
+        assert_eq!(Ftp::probe(&file, &auth_token).await?, LinkStatus::Removed);
+        assert!(Ftp::download(file.clone(), DownloadSetting {}, &auth_token)
+            .await
+            .is_err());
+
+        let credential = Credential::Login {
+            username: String::from("user"),
+            password: String::from("wrong"),
+        };
+        let auth_token = AuthToken::new("127.0.0.1", port, credential, "/drop");
+        assert_eq!(Ftp::probe(&file, &auth_token).await?, LinkStatus::Private);
+        assert!(Ftp::metadata(&file, &auth_token).await.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use super::{file_size, AuthToken, Control, File};
+use crate::utils::skip;
+use futures::{AsyncBufRead, AsyncWriteExt};
+
+pub async fn upload_file<'a>(
+    auth_token: &'a AuthToken,
+    file: &'a File,
+    mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    len: Option<usize>,
+    resume: bool,
+) -> anyhow::Result<()> {
+    let path = auth_token.remote_path(file.get_path());
+    let mut control = Control::connect(auth_token).await?;
+
+    let segments: Vec<&str> = file.get_path().split('/').collect();
+    for i in 1..segments.len() {
+        let parent = auth_token.remote_path(segments[..i].join("/").as_str());
+        control.command(format!("MKD {}", parent).as_str()).await?;
+    }
+
+    // Resuming trusts that the remote prefix matches the source; only a
+    // remote file longer than the source is known to be stale.
+    let offset = match resume {
+        true => file_size(&mut control, path.as_str()).await?.unwrap_or(0),
+        false => 0,
+    };
+    let offset = match len {
+        Some(len) if offset > len => 0,
+        _ => offset,
+    };
+    skip(reader.as_mut(), offset).await?;
+
+    let stream = control.passive().await?;
+    let command = match offset {
+        0 => format!("STOR {}", path),
+        _ => format!("APPE {}", path),
+    };
+    control.expect(Some(command.as_str()), &[125, 150]).await?;
+    let mut data = control.open_data(stream).await?;
+    futures::io::copy(&mut reader, &mut data).await?;
+    data.close().await?;
+    drop(data);
+    control.expect(None, &[226, 250]).await?;
+    control.quit().await;
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Result};
+use surf::{RequestBuilder, Url};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthToken {
+    pub api: Url,
+    pub store: String,
+    pub token: Option<String>,
+}
+
+impl AuthToken {
+    pub fn new(token: &str) -> AuthToken {
+        AuthToken {
+            token: Some(token.to_string()),
+            ..AuthToken::guest()
+        }
+    }
+
+    pub fn guest() -> AuthToken {
+        AuthToken {
+            api: Url::parse("https://api.gofile.io/").unwrap(),
+            store: String::from("https://{}.gofile.io/"),
+            token: None,
+        }
+    }
+
+    pub fn endpoint(mut self, api: &str, store: &str) -> Result<AuthToken> {
+        let mut api = Url::parse(api)?;
+        if !matches!(api.scheme(), "http" | "https") {
+            return Err(anyhow!("unable to recognize the scheme {}.", api.scheme()));
+        }
+        if !api.path().ends_with('/') {
+            let path = format!("{}/", api.path());
+            api.set_path(path.as_str());
+        }
+        if !store.contains("{}") {
+            return Err(anyhow!(
+                "store {} is missing the server placeholder.",
+                store
+            ));
+        }
+        self.api = api;
+        self.store = store.to_string();
+        Ok(self)
+    }
+
+    pub(super) fn authorize(&self, req: RequestBuilder) -> RequestBuilder {
+        match self.token.as_ref() {
+            Some(v) => req.header("Cookie", format!("accountToken={}", v)),
+            None => req,
+        }
+    }
+
+    pub fn api_url(&self, name: &str) -> Result<Url> {
+        Ok(self.api.join(name)?)
+    }
+
+    pub fn store_url(&self, server: &str, name: &str) -> Result<Url> {
+        if server.is_empty()
+            || !server
+                .chars()
+                .all(|v| v.is_ascii_alphanumeric() || v == '-')
+        {
+            return Err(anyhow!("unable to recognize the server {}.", server));
+        }
+        let base = Url::parse(self.store.replace("{}", server).as_str())?;
+        Ok(base.join(name)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authtoken_store_url() -> Result<()> {
+        struct TestCase<'a> {
+            store: Option<&'a str>,
+            server: &'a str,
+            url: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                store: None,
+                server: "store4",
+                url: Some("https://store4.gofile.io/uploadFile"),
+            },
+            TestCase {
+                store: Some("http://127.0.0.1:8080/{}/"),
+                server: "store4",
+                url: Some("http://127.0.0.1:8080/store4/uploadFile"),
+            },
+            TestCase {
+                store: None,
+                server: "evil.com/x",
+                url: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let mut auth_token = AuthToken::guest();
+            if let Some(v) = testcase.store {
+                auth_token = auth_token.endpoint("http://127.0.0.1:8080", v)?;
+            }
+            let url = auth_token.store_url(testcase.server, "uploadFile");
+            match testcase.url {
+                Some(v) => assert_eq!(url?.as_str(), v),
+                None => assert!(url.is_err()),
+            }
+        }
+        assert!(AuthToken::guest()
+            .endpoint("http://127.0.0.1:8080", "http://127.0.0.1:8080/")
+            .is_err());
+        Ok(())
+    }
+}
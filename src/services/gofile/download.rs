@@ -0,0 +1,95 @@
+use super::{read_json, AuthToken, Content, File, FolderData};
+use crate::utils::{send, skip};
+use crate::{FileMetadata, LinkStatus};
+use futures::AsyncBufRead;
+use surf::http::{Method, StatusCode};
+use surf::{Client, Url};
+
+pub async fn get_content<'a>(
+    client: &'a Client,
+    content_id: &'a str,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<Result<FolderData, String>> {
+    let mut url = auth_token.api_url("getContent")?;
+    url.query_pairs_mut().append_pair("contentId", content_id);
+    if let Some(v) = auth_token.token.as_deref() {
+        url.query_pairs_mut().append_pair("token", v);
+    }
+    let mut res = send(client, Method::Get, &url, vec![], None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    read_json(&mut res).await
+}
+
+async fn find_file<'a>(
+    client: &'a Client,
+    file: &'a File,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<Result<Content, String>> {
+    let mut folder = match get_content(client, file.get_code(), auth_token).await? {
+        Ok(v) => v,
+        Err(status) => return Ok(Err(status)),
+    };
+    match folder.contents.remove(file.get_file_id()) {
+        Some(v) if v.kind == "file" => Ok(Ok(v)),
+        _ => Ok(Err(String::from("error-notFound"))),
+    }
+}
+
+pub async fn download_file<'a>(
+    file: &'a File,
+    auth_token: &'a AuthToken,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let client = Client::new();
+
+    let link = match find_file(&client, file, auth_token).await? {
+        Ok(Content { link: Some(v), .. }) => Url::parse(v.as_str())?,
+        Ok(_) => return Err(anyhow::anyhow!("download link is missing.")),
+        Err(status) => return Err(anyhow::anyhow!("unexpected status {}.", status)),
+    };
+    let mut headers = vec![];
+    if offset > 0 {
+        headers.push(("Range", format!("bytes={}-", offset)));
+    }
+    let mut res = send(&client, Method::Get, &link, headers, None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    let mut reader = res.take_body().into_reader();
+    if offset > 0 && res.status() != StatusCode::PartialContent {
+        skip(&mut reader, offset).await?;
+    }
+
+    Ok(reader)
+}
+
+pub async fn file_status<'a>(
+    file: &'a File,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<LinkStatus> {
+    let client = Client::new();
+
+    let status = match find_file(&client, file, auth_token).await? {
+        Ok(v) => LinkStatus::Alive(FileMetadata {
+            name: v.name,
+            len: v.size,
+        }),
+        Err(v) if v == "error-notFound" => LinkStatus::Removed,
+        Err(v)
+            if matches!(
+                v.as_str(),
+                "error-notPublic" | "error-passwordRequired" | "error-wrongToken"
+            ) =>
+        {
+            LinkStatus::Private
+        }
+        Err(v) => LinkStatus::Unknown(format!("unexpected status {}.", v)),
+    };
+    Ok(status)
+}
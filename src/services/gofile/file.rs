@@ -0,0 +1,119 @@
+use anyhow::{anyhow, Error, Result};
+use regex::Regex;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct File {
+    code: String,
+    file_id: String,
+}
+
+impl File {
+    pub fn new(code: &str, file_id: &str) -> File {
+        File {
+            code: code.to_string(),
+            file_id: file_id.to_string(),
+        }
+    }
+
+    pub fn get_code(&self) -> &str {
+        self.code.as_str()
+    }
+
+    pub fn get_file_id(&self) -> &str {
+        self.file_id.as_str()
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        let re = Regex::new(r"^https://gofile\.io/d/([\w\-]+)#([\w\-]+)$").unwrap();
+        let cap = match re.captures(src) {
+            Some(v) => v,
+            None => return Err(anyhow!("unable to recognize the id.")),
+        };
+
+        Ok(File::new(&cap[1], &cap[2]))
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+impl From<File> for String {
+    fn from(file: File) -> String {
+        format!("https://gofile.io/d/{}#{}", file.code, file.file_id)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Folder {
+    id: String,
+    code: String,
+}
+
+impl Folder {
+    pub fn new(id: &str, code: &str) -> Folder {
+        Folder {
+            id: id.to_string(),
+            code: code.to_string(),
+        }
+    }
+
+    pub fn get_id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn get_code(&self) -> &str {
+        self.code.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_tryfrom_str() -> Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            file: Option<(&'a str, &'a str)>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "https://gofile.io/d/Ab12Cd#0b8f0a4e-6c1d-4f6b-9a7e-2d3c4b5a6f70",
+                file: Some(("Ab12Cd", "0b8f0a4e-6c1d-4f6b-9a7e-2d3c4b5a6f70")),
+            },
+            TestCase {
+                src: "https://gofile.io/d/Ab12Cd",
+                file: None,
+            },
+            TestCase {
+                src: "https://example.com/d/Ab12Cd#f1",
+                file: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let file = File::try_from(testcase.src);
+            match testcase.file {
+                Some((code, file_id)) => {
+                    let file = file?;
+                    assert_eq!(file.get_code(), code);
+                    assert_eq!(file.get_file_id(), file_id);
+                    let uri: String = file.into();
+                    assert_eq!(uri, testcase.src);
+                }
+                None => assert!(file.is_err()),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use super::{form, get_content, read_ok, AccountData, AuthToken, File, Folder, FolderData};
+use crate::utils::send;
+use crate::FileMetadata;
+use surf::http::Method;
+use surf::Client;
+
+fn get_token(auth_token: &AuthToken) -> anyhow::Result<&str> {
+    match auth_token.token.as_deref() {
+        Some(v) => Ok(v),
+        None => Err(anyhow::anyhow!("account token is required.")),
+    }
+}
+
+pub async fn create_folder<'a>(
+    parent_id: &'a str,
+    name: &'a str,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<Folder> {
+    let client = Client::new();
+
+    let token = get_token(auth_token)?;
+    let url = auth_token.api_url("createFolder")?;
+    let body = form(&[
+        ("parentFolderId", parent_id),
+        ("folderName", name),
+        ("token", token),
+    ])?;
+    let mut res = send(&client, Method::Put, &url, vec![], Some(body), |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    let data: FolderData = read_ok(&mut res).await?;
+
+    Ok(Folder::new(data.id.as_str(), data.code.as_str()))
+}
+
+pub async fn move_content<'a>(
+    content_ids: &'a [&'a str],
+    folder_id: &'a str,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<()> {
+    let client = Client::new();
+
+    let token = get_token(auth_token)?;
+    let url = auth_token.api_url("moveContent")?;
+    let ids = content_ids.join(",");
+    let body = form(&[
+        ("contentsId", ids.as_str()),
+        ("folderIdDest", folder_id),
+        ("token", token),
+    ])?;
+    let mut res = send(&client, Method::Put, &url, vec![], Some(body), |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    read_ok::<serde_json::Value>(&mut res).await?;
+
+    Ok(())
+}
+
+pub async fn delete_content<'a>(
+    content_ids: &'a [&'a str],
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<()> {
+    let client = Client::new();
+
+    let token = get_token(auth_token)?;
+    let url = auth_token.api_url("deleteContent")?;
+    let ids = content_ids.join(",");
+    let body = form(&[("contentsId", ids.as_str()), ("token", token)])?;
+    let mut res = send(&client, Method::Delete, &url, vec![], Some(body), |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    read_ok::<serde_json::Value>(&mut res).await?;
+
+    Ok(())
+}
+
+pub async fn root_folder(auth_token: &AuthToken) -> anyhow::Result<String> {
+    let client = Client::new();
+
+    let token = get_token(auth_token)?;
+    let mut url = auth_token.api_url("getAccountDetails")?;
+    url.query_pairs_mut().append_pair("token", token);
+    let mut res = send(&client, Method::Get, &url, vec![], None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    let data: AccountData = read_ok(&mut res).await?;
+
+    Ok(data.root_folder)
+}
+
+pub async fn list_files(auth_token: &AuthToken) -> anyhow::Result<Vec<(File, FileMetadata)>> {
+    let client = Client::new();
+
+    let mut files = vec![];
+    let mut folders = vec![root_folder(auth_token).await?];
+    while let Some(id) = folders.pop() {
+        let folder = match get_content(&client, id.as_str(), auth_token).await? {
+            Ok(v) => v,
+            Err(status) => return Err(anyhow::anyhow!("unexpected status {}.", status)),
+        };
+        for (_, content) in folder.contents {
+            match content.kind.as_str() {
+                "folder" => folders.push(content.id),
+                "file" => files.push((
+                    File::new(folder.code.as_str(), content.id.as_str()),
+                    FileMetadata {
+                        name: content.name,
+                        len: content.size,
+                    },
+                )),
+                _ => {}
+            }
+        }
+    }
+    files.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+
+    Ok(files)
+}
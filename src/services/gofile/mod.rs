@@ -0,0 +1,524 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod request;
+use request::*;
+mod download;
+use download::*;
+mod folder;
+use folder::*;
+mod upload;
+use upload::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Service,
+    Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+
+pub struct Gofile {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Uploaded {
+    pub file: File,
+    pub folder: Folder,
+    pub guest_token: Option<String>,
+}
+
+impl Gofile {
+    pub async fn upload_file(
+        name: &str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: UploadSetting,
+        auth_token: &AuthToken,
+    ) -> anyhow::Result<Uploaded> {
+        upload_file(name, reader, len, &setting, auth_token).await
+    }
+
+    pub async fn root_folder(auth_token: &AuthToken) -> anyhow::Result<String> {
+        root_folder(auth_token).await
+    }
+
+    pub async fn create_folder(
+        parent_id: &str,
+        name: &str,
+        auth_token: &AuthToken,
+    ) -> anyhow::Result<Folder> {
+        create_folder(parent_id, name, auth_token).await
+    }
+
+    pub async fn move_content(
+        content_ids: &[&str],
+        folder_id: &str,
+        auth_token: &AuthToken,
+    ) -> anyhow::Result<()> {
+        move_content(content_ids, folder_id, auth_token).await
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UploadSetting {
+    pub folder_id: Option<String>,
+}
+
+impl Service for Gofile {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for Gofile {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, auth_token, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Gofile {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, auth_token, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for Gofile {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        match file_status(file, auth_token).await? {
+            LinkStatus::Alive(metadata) => Ok(metadata),
+            LinkStatus::Removed => Err(anyhow::anyhow!("file is removed.")),
+            LinkStatus::Private => Err(anyhow::anyhow!("file is private.")),
+            LinkStatus::Unknown(e) => Err(anyhow::anyhow!(e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Probe for Gofile {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        file_status(file, auth_token).await
+    }
+}
+
+#[async_trait]
+impl Delete for Gofile {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        delete_content(&[file.get_file_id()], auth_token).await
+    }
+}
+
+#[async_trait]
+impl List for Gofile {
+    async fn list<'a>(
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Vec<(Self::File, FileMetadata)>> {
+        list_files(auth_token).await
+    }
+}
+
+#[async_trait]
+impl Upload for Gofile {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::MAX
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        // A guest upload's token is only returned by `Gofile::upload_file`.
+        let uploaded = upload_file(name, reader, len, &setting, auth_token).await?;
+        Ok(uploaded.file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, DownloadSetting, File, Gofile, UploadSetting};
+    use crate::utils::{serve, MockRequest, MockResponse};
+    use crate::{
+        Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Upload,
+    };
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+    use serde_json::{json, Map, Value};
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    use surf::Url;
+
+    struct MockFolder {
+        code: String,
+        parent: Option<String>,
+        owner: String,
+    }
+
+    struct MockFile {
+        folder: String,
+        name: String,
+        data: Vec<u8>,
+    }
+
+    #[derive(Default)]
+    struct Store {
+        base: String,
+        count: usize,
+        accounts: BTreeMap<String, String>,
+        folders: BTreeMap<String, MockFolder>,
+        files: BTreeMap<String, MockFile>,
+    }
+
+    impl Store {
+        fn next(&mut self, prefix: &str) -> String {
+            self.count += 1;
+            format!("{}{}", prefix, self.count)
+        }
+
+        fn folder(&mut self, parent: Option<String>, owner: &str) -> String {
+            let id = self.next("d");
+            let code = self.next("C");
+            let owner = owner.to_string();
+            self.folders.insert(
+                id.clone(),
+                MockFolder {
+                    code,
+                    parent,
+                    owner,
+                },
+            );
+            id
+        }
+
+        fn owns(&self, token: Option<&String>, id: &str) -> bool {
+            let folder = match self.files.get(id) {
+                Some(v) => v.folder.as_str(),
+                None => id,
+            };
+            matches!(
+                (self.folders.get(folder), token),
+                (Some(v), Some(token)) if v.owner == *token
+            )
+        }
+    }
+
+    fn ok(data: Value) -> MockResponse {
+        MockResponse::new(200).body(json!({"status": "ok", "data": data}).to_string())
+    }
+
+    fn status(status: &str) -> MockResponse {
+        MockResponse::new(200).body(json!({"status": status, "data": {}}).to_string())
+    }
+
+    fn fields(src: &[u8]) -> BTreeMap<String, String> {
+        let url = format!("http://mock/?{}", String::from_utf8_lossy(src));
+        Url::parse(url.as_str())
+            .unwrap()
+            .query_pairs()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn handle(store: &mut Store, req: MockRequest) -> MockResponse {
+        let (path, query) = req.path.split_once('?').unwrap_or((req.path.as_str(), ""));
+        let query = fields(query.as_bytes());
+        let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        match (req.method.as_str(), segments.as_slice()) {
+            ("GET", ["api", "getServer"]) => ok(json!({"server": "store1"})),
+            ("POST", ["store1", "uploadFile"]) => {
+                let form = req.form();
+                let (name, data) = match form.get("file") {
+                    Some((Some(name), data)) => (name.clone(), data.clone()),
+                    _ => return status("error-noFile"),
+                };
+                let text = |k: &str| {
+                    form.get(k)
+                        .map(|(_, v)| String::from_utf8_lossy(v).to_string())
+                };
+                let (token, guest_token) = match text("token") {
+                    Some(v) if store.accounts.contains_key(&v) => (v, None),
+                    Some(_) => return status("error-wrongToken"),
+                    None => {
+                        let token = store.next("guest");
+                        let root = store.folder(None, token.as_str());
+                        store.accounts.insert(token.clone(), root);
+                        (token.clone(), Some(token))
+                    }
+                };
+                let folder = match text("folderId") {
+                    Some(v) if store.owns(Some(&token), v.as_str()) => v,
+                    Some(_) => return status("error-notFound"),
+                    None => {
+                        let root = store.accounts.get(&token).cloned();
+                        store.folder(root, token.as_str())
+                    }
+                };
+                let id = store.next("f");
+                let code = store.folders.get(&folder).unwrap().code.clone();
+                store.files.insert(
+                    id.clone(),
+                    MockFile {
+                        folder: folder.clone(),
+                        name: name.clone(),
+                        data,
+                    },
+                );
+                let mut data = json!({
+                    "downloadPage": format!("https://gofile.io/d/{}", code),
+                    "code": code,
+                    "parentFolder": folder,
+                    "fileId": id,
+                    "fileName": name,
+                });
+                if let Some(v) = guest_token {
+                    data["guestToken"] = json!(v);
+                }
+                ok(data)
+            }
+            ("GET", ["store1", "download", id, _]) => {
+                let file = match store.files.get(*id) {
+                    Some(v) => v,
+                    None => return MockResponse::new(404),
+                };
+                match req
+                    .header("range")
+                    .and_then(|v| v.strip_prefix("bytes="))
+                    .and_then(|v| v.strip_suffix('-'))
+                {
+                    Some(start) => {
+                        let start: usize = start.parse().unwrap();
+                        MockResponse::new(206).body(&file.data[start..])
+                    }
+                    None => MockResponse::new(200).body(file.data.clone()),
+                }
+            }
+            ("GET", ["api", "getContent"]) => {
+                let content_id = query.get("contentId").cloned().unwrap_or_default();
+                let found = store
+                    .folders
+                    .iter()
+                    .find(|(id, v)| **id == content_id || v.code == content_id);
+                let (id, folder) = match found {
+                    Some(v) => v,
+                    None => return status("error-notFound"),
+                };
+                let mut contents = Map::new();
+                for (child, v) in store.folders.iter() {
+                    if v.parent.as_deref() == Some(id.as_str()) {
+                        let content =
+                            json!({"id": child, "type": "folder", "name": child, "code": v.code});
+                        contents.insert(child.clone(), content);
+                    }
+                }
+                for (child, v) in store.files.iter() {
+                    if v.folder == *id {
+                        let link = format!("{}/store1/download/{}/{}", store.base, child, v.name);
+                        let content = json!({
+                            "id": child,
+                            "type": "file",
+                            "name": v.name,
+                            "size": v.data.len(),
+                            "link": link,
+                        });
+                        contents.insert(child.clone(), content);
+                    }
+                }
+                ok(json!({"id": id, "type": "folder", "code": folder.code, "contents": contents}))
+            }
+            ("GET", ["api", "getAccountDetails"]) => {
+                match query.get("token").and_then(|v| store.accounts.get(v)) {
+                    Some(v) => ok(json!({"tier": "guest", "rootFolder": v})),
+                    None => status("error-wrongToken"),
+                }
+            }
+            ("PUT", ["api", "createFolder"]) => {
+                let form = fields(req.body.as_slice());
+                let parent = form.get("parentFolderId").cloned().unwrap_or_default();
+                let token = form.get("token");
+                if !store.owns(token, parent.as_str()) {
+                    return status("error-notFound");
+                }
+                let id = store.folder(Some(parent), token.unwrap());
+                let code = store.folders.get(&id).unwrap().code.clone();
+                let name = form.get("folderName");
+                ok(json!({"id": id, "type": "folder", "code": code, "name": name}))
+            }
+            ("PUT", ["api", "moveContent"]) => {
+                let form = fields(req.body.as_slice());
+                let token = form.get("token");
+                let dest = form.get("folderIdDest").cloned().unwrap_or_default();
+                let ids: Vec<String> = form["contentsId"].split(',').map(String::from).collect();
+                if !store.owns(token, dest.as_str()) || ids.iter().any(|v| !store.owns(token, v)) {
+                    return status("error-notFound");
+                }
+                for id in ids {
+                    if let Some(v) = store.files.get_mut(&id) {
+                        v.folder = dest.clone();
+                    }
+                }
+                ok(json!({}))
+            }
+            ("DELETE", ["api", "deleteContent"]) => {
+                let form = fields(req.body.as_slice());
+                let token = form.get("token");
+                let ids: Vec<String> = form["contentsId"].split(',').map(String::from).collect();
+                if ids.iter().any(|v| !store.owns(token, v)) {
+                    return status("error-notFound");
+                }
+                for id in ids {
+                    store.files.remove(&id);
+                }
+                ok(json!({}))
+            }
+            _ => MockResponse::new(405),
+        }
+    }
+
+    #[tokio::test]
+    async fn gofile_roundtrip() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            name: &'a str,
+            len: Option<usize>,
+            same_folder: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "a.txt",
+                len: Some(8),
+                same_folder: false,
+            },
+            TestCase {
+                name: "b.bin",
+                len: None,
+                same_folder: true,
+            },
+        ];
+
+        let store = Arc::new(Mutex::new(Store::default()));
+        let addr = {
+            let store = store.clone();
+            serve(move |req| handle(&mut store.lock().unwrap(), req))
+        };
+        store.lock().unwrap().base = addr.clone();
+        let api = format!("{}/api/", addr);
+        let store_url = format!("{}/{{}}/", addr);
+        let guest = AuthToken::guest().endpoint(api.as_str(), store_url.as_str())?;
+
+        let mut auth_token = guest.clone();
+        let mut folder = None;
+        let mut files = vec![];
+        for testcase in testcases {
+            let setting = UploadSetting {
+                folder_id: match testcase.same_folder {
+                    true => folder.clone(),
+                    false => None,
+                },
+            };
+            let reader = Box::new(Cursor::new("abcdefgh"));
+            let uploaded =
+                Gofile::upload_file(testcase.name, reader, testcase.len, setting, &auth_token)
+                    .await?;
+            if let Some(v) = uploaded.guest_token.as_deref() {
+                auth_token = AuthToken {
+                    token: Some(v.to_string()),
+                    ..auth_token
+                };
+            }
+            if testcase.same_folder {
+                assert_eq!(folder.as_deref(), Some(uploaded.folder.get_id()));
+            }
+            folder = Some(uploaded.folder.get_id().to_string());
+            assert_eq!(uploaded.file.get_code(), uploaded.folder.get_code());
+
+            let file = File::try_from(String::from(uploaded.file))?;
+            let mut data = String::new();
+            Gofile::download(file.clone(), DownloadSetting {}, &guest)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, "abcdefgh");
+
+            let mut data = String::new();
+            Gofile::download_range(file.clone(), DownloadSetting {}, &guest, 5)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, "fgh");
+
+            let metadata = FileMetadata {
+                name: testcase.name.to_string(),
+                len: Some(8),
+            };
+            assert_eq!(Gofile::metadata(&file, &guest).await?, metadata);
+            files.push((file, metadata));
+        }
+        assert!(auth_token.token.is_some());
+
+        let root = Gofile::root_folder(&auth_token).await?;
+        let sub = Gofile::create_folder(root.as_str(), "sub", &auth_token).await?;
+        let (file, metadata) = files.pop().unwrap();
+        Gofile::move_content(&[file.get_file_id()], sub.get_id(), &auth_token).await?;
+        assert_eq!(Gofile::probe(&file, &guest).await?, LinkStatus::Removed);
+        let file = File::new(sub.get_code(), file.get_file_id());
+        assert_eq!(
+            Gofile::probe(&file, &guest).await?,
+            LinkStatus::Alive(metadata.clone())
+        );
+        files.push((file, metadata));
+        assert_eq!(Gofile::list(&auth_token).await?, files);
+
+        let (file, _) = files[0].clone();
+        assert_eq!(
+            Gofile::delete(file.clone(), &guest)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "account token is required."
+        );
+        let wrong = AuthToken::new("wrong").endpoint(api.as_str(), store_url.as_str())?;
+        assert!(Gofile::delete(file.clone(), &wrong).await.is_err());
+        assert!(Gofile::create_folder(root.as_str(), "x", &wrong)
+            .await
+            .is_err());
+        Gofile::delete(file.clone(), &auth_token).await?;
+        assert_eq!(Gofile::probe(&file, &guest).await?, LinkStatus::Removed);
+        assert!(Gofile::metadata(&file, &guest).await.is_err());
+
+        let upload = Gofile::upload(
+            "c.txt",
+            Box::new(Cursor::new("abcdefgh")),
+            Some(8),
+            UploadSetting::default(),
+            &wrong,
+        );
+        assert!(upload.await.is_err());
+
+        Ok(())
+    }
+}
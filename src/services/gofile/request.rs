@@ -0,0 +1,147 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use surf::{Body, Response};
+
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    status: String,
+    #[serde(default)]
+    data: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ServerData {
+    pub server: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadData {
+    pub code: String,
+    pub parent_folder: String,
+    pub file_id: String,
+    pub guest_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountData {
+    pub root_folder: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Content {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub size: Option<usize>,
+    pub link: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FolderData {
+    pub id: String,
+    pub code: String,
+    #[serde(default)]
+    pub contents: BTreeMap<String, Content>,
+}
+
+pub fn parse<T: DeserializeOwned>(problem: &[u8]) -> anyhow::Result<Result<T, String>> {
+    let envelope: Envelope = serde_json::from_slice(problem)?;
+    if envelope.status != "ok" {
+        return Ok(Err(envelope.status));
+    }
+    Ok(Ok(serde_json::from_value(envelope.data)?))
+}
+
+pub async fn read_json<T: DeserializeOwned>(
+    res: &mut Response,
+) -> anyhow::Result<Result<T, String>> {
+    let body = match res.body_bytes().await {
+        Ok(v) => v,
+        Err(e) => return Err(e.into_inner()),
+    };
+    match parse(body.as_slice()) {
+        Err(_) if !res.status().is_success() => {
+            Err(anyhow::anyhow!("unexpected status {}.", res.status()))
+        }
+        v => v,
+    }
+}
+
+pub async fn read_ok<T: DeserializeOwned>(res: &mut Response) -> anyhow::Result<T> {
+    match read_json(res).await? {
+        Ok(v) => Ok(v),
+        Err(status) => Err(anyhow::anyhow!("unexpected status {}.", status)),
+    }
+}
+
+pub fn form(fields: &[(&str, &str)]) -> anyhow::Result<Body> {
+    match Body::from_form(&fields) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(e.into_inner()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FolderData, ServerData};
+
+    #[test]
+    fn parse() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            problem: &'a str,
+            solution: Result<&'a str, &'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                problem: r#"{"status":"ok","data":{"server":"store4"}}"#,
+                solution: Ok("store4"),
+            },
+            TestCase {
+                problem: r#"{"status":"error-notFound","data":{}}"#,
+                solution: Err("error-notFound"),
+            },
+            TestCase {
+                problem: r#"{"status":"noServer"}"#,
+                solution: Err("noServer"),
+            },
+        ];
+
+        for testcase in testcases {
+            let solution = super::parse::<ServerData>(testcase.problem.as_bytes())?;
+            assert_eq!(
+                solution
+                    .as_ref()
+                    .map(|v| v.server.as_str())
+                    .map_err(|v| v.as_str()),
+                testcase.solution
+            );
+        }
+        assert!(super::parse::<ServerData>(b"<html>").is_err());
+        assert!(super::parse::<ServerData>(br#"{"status":"ok","data":{}}"#).is_err());
+
+        let problem = r#"{"status":"ok","data":{"isOwner":true,"id":"d1","type":"folder","name":"root","code":"Ab12","childs":["f1","d2"],"contents":{"f1":{"id":"f1","type":"file","name":"a.txt","size":8,"md5":"e8dc4081b13434b45189a720b77b6818","link":"https://store4.gofile.io/download/f1/a.txt"},"d2":{"id":"d2","type":"folder","name":"sub","code":"Cd34","childs":[]}}}}"#;
+        let folder = match super::parse::<FolderData>(problem.as_bytes())? {
+            Ok(v) => v,
+            Err(e) => return Err(anyhow::anyhow!("unexpected status {}.", e)),
+        };
+        assert_eq!((folder.id.as_str(), folder.code.as_str()), ("d1", "Ab12"));
+        let file = folder.contents.get("f1").unwrap();
+        assert_eq!(
+            (file.kind.as_str(), file.size, file.link.as_deref()),
+            (
+                "file",
+                Some(8),
+                Some("https://store4.gofile.io/download/f1/a.txt")
+            )
+        );
+        let sub = folder.contents.get("d2").unwrap();
+        assert_eq!((sub.kind.as_str(), sub.name.as_str()), ("folder", "sub"));
+        Ok(())
+    }
+}
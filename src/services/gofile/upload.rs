@@ -0,0 +1,69 @@
+use super::{read_ok, AuthToken, File, Folder, ServerData, UploadData, UploadSetting, Uploaded};
+use crate::utils::{
+    gen_boundary, send, Multipart, MultipartContentEnum, MultipartField, BOUNDARY_CHARSET,
+};
+use futures::AsyncBufRead;
+use surf::http::Method;
+use surf::Client;
+
+pub async fn get_server<'a>(
+    client: &'a Client,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<String> {
+    let url = auth_token.api_url("getServer")?;
+    let mut res = send(client, Method::Get, &url, vec![], None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    let data: ServerData = read_ok(&mut res).await?;
+
+    Ok(data.server)
+}
+
+pub async fn upload_file<'a>(
+    name: &'a str,
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    len: Option<usize>,
+    setting: &'a UploadSetting,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<Uploaded> {
+    let client = Client::new();
+
+    let server = get_server(&client, auth_token).await?;
+    let url = auth_token.store_url(server.as_str(), "uploadFile")?;
+
+    let boundary = gen_boundary(16, BOUNDARY_CHARSET)?;
+    let content_type = format!("multipart/form-data; boundary={}", boundary);
+    let body = {
+        let mut multipart = Multipart::new(MultipartField {
+            name: "file",
+            data: MultipartContentEnum::Reader(reader, name, len),
+        });
+        if let Some(v) = auth_token.token.as_deref() {
+            multipart = multipart.chain(MultipartField {
+                name: "token",
+                data: MultipartContentEnum::Str(v),
+            });
+        }
+        if let Some(v) = setting.folder_id.as_deref() {
+            multipart = multipart.chain(MultipartField {
+                name: "folderId",
+                data: MultipartContentEnum::Str(v),
+            });
+        }
+        multipart.into_body(boundary.as_str())
+    };
+
+    let headers = vec![("Content-Type", content_type)];
+    let mut res = send(&client, Method::Post, &url, headers, Some(body), |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    let data: UploadData = read_ok(&mut res).await?;
+
+    Ok(Uploaded {
+        file: File::new(data.code.as_str(), data.file_id.as_str()),
+        folder: Folder::new(data.parent_folder.as_str(), data.code.as_str()),
+        guest_token: data.guest_token,
+    })
+}
@@ -0,0 +1,24 @@
+use surf::RequestBuilder;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AuthToken {
+    pub headers: Vec<(String, String)>,
+}
+
+impl AuthToken {
+    pub fn empty() -> AuthToken {
+        AuthToken { headers: vec![] }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> AuthToken {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub(super) fn authorize(&self, mut req: RequestBuilder) -> RequestBuilder {
+        for (k, v) in self.headers.iter() {
+            req = req.header(k.as_str(), v.as_str());
+        }
+        req
+    }
+}
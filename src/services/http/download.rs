@@ -0,0 +1,502 @@
+use super::{AuthToken, DownloadSetting};
+use crate::utils::{percent_decode, skip};
+use crate::{FileMetadata, LinkStatus};
+use futures::AsyncBufRead;
+use surf::http::{Method, StatusCode};
+use surf::{Client, Response, Url};
+
+pub async fn send<'a>(
+    client: &'a Client,
+    method: Method,
+    uri: &'a str,
+    headers: Vec<(&'a str, String)>,
+    auth_token: &'a AuthToken,
+    max_redirects: usize,
+) -> anyhow::Result<(Url, Response)> {
+    let mut url = Url::parse(uri)?;
+    let origin = url.origin();
+    let mut trusted = true;
+    let mut redirects = 0;
+
+    loop {
+        trusted = trusted && url.origin() == origin;
+        let res =
+            crate::utils::send(
+                client,
+                method,
+                &url,
+                headers.clone(),
+                None,
+                |req| match trusted {
+                    true => auth_token.authorize(req),
+                    false => req,
+                },
+            )
+            .await?;
+
+        let location = match res.status() {
+            StatusCode::MovedPermanently
+            | StatusCode::Found
+            | StatusCode::SeeOther
+            | StatusCode::TemporaryRedirect
+            | StatusCode::PermanentRedirect => res.header("Location").map(|v| v.last().to_string()),
+            _ => None,
+        };
+        let location = match location {
+            Some(v) => v,
+            None => return Ok((url, res)),
+        };
+
+        redirects += 1;
+        if redirects > max_redirects {
+            return Err(anyhow::anyhow!("too many redirects."));
+        }
+        url = url.join(location.as_str())?;
+    }
+}
+
+pub async fn download_file<'a>(
+    uri: &'a str,
+    setting: &'a DownloadSetting,
+    auth_token: &'a AuthToken,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let client = Client::new();
+
+    let mut headers = vec![];
+    if offset > 0 {
+        headers.push(("Range", format!("bytes={}-", offset)));
+        if let Some(validator) = setting.etag.as_ref().or(setting.last_modified.as_ref()) {
+            headers.push(("If-Range", validator.clone()));
+        }
+    }
+
+    let (_, mut res) = send(
+        &client,
+        Method::Get,
+        uri,
+        headers,
+        auth_token,
+        setting.max_redirects,
+    )
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+    validate(&res, setting)?;
+
+    let mut reader = res.take_body().into_reader();
+    if offset > 0 && res.status() != StatusCode::PartialContent {
+        skip(&mut reader, offset).await?;
+    }
+
+    Ok(reader)
+}
+
+pub async fn file_metadata<'a>(
+    uri: &'a str,
+    auth_token: &'a AuthToken,
+    max_redirects: usize,
+) -> anyhow::Result<(FileMetadata, Option<String>, Option<String>)> {
+    let client = Client::new();
+
+    let (url, res) = probe_file(&client, uri, auth_token, max_redirects).await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    Ok(parse_metadata(&url, &res))
+}
+
+pub async fn file_status<'a>(
+    uri: &'a str,
+    auth_token: &'a AuthToken,
+    max_redirects: usize,
+) -> anyhow::Result<LinkStatus> {
+    let client = Client::new();
+
+    let (url, res) = probe_file(&client, uri, auth_token, max_redirects).await?;
+    let status = match res.status() {
+        StatusCode::NotFound | StatusCode::Gone => LinkStatus::Removed,
+        StatusCode::Unauthorized | StatusCode::Forbidden => LinkStatus::Private,
+        v if v.is_success() => LinkStatus::Alive(parse_metadata(&url, &res).0),
+        v => LinkStatus::Unknown(format!("unexpected status {}.", v)),
+    };
+
+    Ok(status)
+}
+
+async fn probe_file<'a>(
+    client: &'a Client,
+    uri: &'a str,
+    auth_token: &'a AuthToken,
+    max_redirects: usize,
+) -> anyhow::Result<(Url, Response)> {
+    let headers = vec![("Range", String::from("bytes=0-0"))];
+    send(client, Method::Get, uri, headers, auth_token, max_redirects).await
+}
+
+fn parse_metadata(url: &Url, res: &Response) -> (FileMetadata, Option<String>, Option<String>) {
+    let name = match res.header("Content-Disposition") {
+        Some(v) => get_filename(v.last().as_str()),
+        None => None,
+    };
+    let name = name.unwrap_or_else(|| get_url_filename(url));
+    let len = match res.status() {
+        StatusCode::PartialContent => res
+            .header("Content-Range")
+            .and_then(|v| get_total_len(v.last().as_str())),
+        _ => res
+            .header("Content-Length")
+            .and_then(|v| v.last().as_str().parse().ok()),
+    };
+    let etag = res.header("ETag").map(|v| v.last().to_string());
+    let last_modified = res.header("Last-Modified").map(|v| v.last().to_string());
+
+    (FileMetadata { name, len }, etag, last_modified)
+}
+
+fn get_total_len(content_range: &str) -> Option<usize> {
+    content_range.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+fn validate<'a>(res: &'a Response, setting: &'a DownloadSetting) -> anyhow::Result<()> {
+    let pairs = [
+        ("ETag", setting.etag.as_ref()),
+        ("Last-Modified", setting.last_modified.as_ref()),
+    ];
+    for (name, expected) in pairs {
+        let expected = match expected {
+            Some(v) => v,
+            None => continue,
+        };
+        if let Some(actual) = res.header(name) {
+            if actual.last().as_str() != expected {
+                return Err(anyhow::anyhow!("file has changed, {} do not match.", name));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn get_filename(disposition: &str) -> Option<String> {
+    let mut fallback = None;
+    for param in disposition.split(';').map(|v| v.trim()) {
+        let (key, value) = match param.split_once('=') {
+            Some(v) => v,
+            None => continue,
+        };
+        match key.trim().to_lowercase().as_str() {
+            "filename*" => {
+                let value = value.trim();
+                let encoded = match value.splitn(3, '\'').nth(2) {
+                    Some(v) => v,
+                    None => value,
+                };
+                if let Some(v) = percent_decode(encoded) {
+                    return Some(v);
+                }
+            }
+            "filename" => {
+                fallback = Some(value.trim().trim_matches('"').to_string());
+            }
+            _ => {}
+        }
+    }
+
+    fallback.filter(|v| !v.is_empty())
+}
+
+fn get_url_filename(url: &Url) -> String {
+    let segment = url
+        .path_segments()
+        .and_then(|mut v| v.next_back())
+        .and_then(percent_decode)
+        .unwrap_or_default();
+    if segment.is_empty() {
+        return String::from("download");
+    }
+    segment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{AuthToken, DownloadSetting};
+    use crate::utils::{serve, MockRequest, MockResponse};
+    use crate::{FileMetadata, LinkStatus};
+    use futures::AsyncReadExt;
+    use std::sync::{Arc, Mutex};
+
+    type Seen = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+    #[test]
+    fn get_filename_test() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            problem: &'a str,
+            solution: Option<&'a str>,
+        }
+        let function = super::get_filename;
+
+        let testcases = [
+            TestCase {
+                problem: "attachment; filename=\"a b.txt\"",
+                solution: Some("a b.txt"),
+            },
+            TestCase {
+                problem: "attachment; filename=plain.bin",
+                solution: Some("plain.bin"),
+            },
+            TestCase {
+                problem:
+                    "attachment; filename=\"fallback.txt\"; filename*=UTF-8''%E2%82%AC%20rates.txt",
+                solution: Some("€ rates.txt"),
+            },
+            TestCase {
+                problem: "inline",
+                solution: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let solution = function(testcase.problem);
+            assert_eq!(solution.as_deref(), testcase.solution);
+        }
+        Ok(())
+    }
+
+    fn server() -> String {
+        serve(|req| {
+            let data = "abcdefgh";
+            match req.path.as_str() {
+                "/redirect" => MockResponse::new(302).header("Location", "/files/a%20b.txt"),
+                "/loop" => MockResponse::new(302).header("Location", "/loop"),
+                "/gone" => MockResponse::new(410),
+                "/private" => MockResponse::new(403),
+                "/named" => MockResponse::new(200)
+                    .header("Content-Disposition", "attachment; filename=\"named.txt\"")
+                    .body(data),
+                "/files/a%20b.txt" => {
+                    let etag = "\"v1\"";
+                    let range = req
+                        .header("range")
+                        .and_then(|v| v.strip_prefix("bytes="))
+                        .and_then(|v| v.strip_suffix('-'))
+                        .and_then(|v| v.parse::<usize>().ok());
+                    let fresh = match req.header("if-range") {
+                        Some(v) => v == etag,
+                        None => true,
+                    };
+                    match range {
+                        Some(offset) if fresh => MockResponse::new(206)
+                            .header("ETag", etag)
+                            .header(
+                                "Content-Range",
+                                format!("bytes {}-{}/{}", offset, data.len() - 1, data.len())
+                                    .as_str(),
+                            )
+                            .body(&data.as_bytes()[offset..]),
+                        _ => MockResponse::new(200).header("ETag", etag).body(data),
+                    }
+                }
+                _ => MockResponse::new(404),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn download_file_test() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            path: &'a str,
+            etag: Option<&'a str>,
+            offset: usize,
+            data: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                path: "/redirect",
+                etag: None,
+                offset: 0,
+                data: Some("abcdefgh"),
+            },
+            TestCase {
+                path: "/redirect",
+                etag: Some("\"v1\""),
+                offset: 3,
+                data: Some("defgh"),
+            },
+            TestCase {
+                path: "/named",
+                etag: None,
+                offset: 5,
+                data: Some("fgh"),
+            },
+            TestCase {
+                path: "/redirect",
+                etag: Some("\"v0\""),
+                offset: 3,
+                data: None,
+            },
+            TestCase {
+                path: "/loop",
+                etag: None,
+                offset: 0,
+                data: None,
+            },
+            TestCase {
+                path: "/gone",
+                etag: None,
+                offset: 0,
+                data: None,
+            },
+        ];
+
+        let base = server();
+        for testcase in testcases {
+            let uri = format!("{}{}", base, testcase.path);
+            let setting = DownloadSetting {
+                etag: testcase.etag.map(|v| v.to_string()),
+                ..DownloadSetting::default()
+            };
+            let result = async {
+                let mut reader = super::download_file(
+                    uri.as_str(),
+                    &setting,
+                    &AuthToken::empty(),
+                    testcase.offset,
+                )
+                .await?;
+                let mut data = String::new();
+                reader.read_to_string(&mut data).await?;
+                anyhow::Ok(data)
+            }
+            .await;
+            match testcase.data {
+                Some(v) => assert_eq!(result?, v),
+                None => assert!(result.is_err()),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_status_test() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            path: &'a str,
+            status: LinkStatus,
+        }
+
+        let testcases = [
+            TestCase {
+                path: "/redirect",
+                status: LinkStatus::Alive(FileMetadata {
+                    name: String::from("a b.txt"),
+                    len: Some(8),
+                }),
+            },
+            TestCase {
+                path: "/named",
+                status: LinkStatus::Alive(FileMetadata {
+                    name: String::from("named.txt"),
+                    len: Some(8),
+                }),
+            },
+            TestCase {
+                path: "/gone",
+                status: LinkStatus::Removed,
+            },
+            TestCase {
+                path: "/missing",
+                status: LinkStatus::Removed,
+            },
+            TestCase {
+                path: "/private",
+                status: LinkStatus::Private,
+            },
+        ];
+
+        let base = server();
+        for testcase in testcases {
+            let uri = format!("{}{}", base, testcase.path);
+            let status = super::file_status(uri.as_str(), &AuthToken::empty(), 10).await?;
+            assert_eq!(status, testcase.status);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redirect_auth_test() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            path: &'a str,
+            seen: Vec<(&'a str, Option<&'a str>)>,
+        }
+
+        let testcases = [
+            TestCase {
+                path: "/same",
+                seen: vec![("/same", Some("Bearer t")), ("/file", Some("Bearer t"))],
+            },
+            TestCase {
+                path: "/cross",
+                seen: vec![("/cross", Some("Bearer t")), ("/other", None)],
+            },
+            TestCase {
+                path: "/bounce",
+                seen: vec![
+                    ("/bounce", Some("Bearer t")),
+                    ("/back", None),
+                    ("/file", None),
+                ],
+            },
+        ];
+
+        let seen: Seen = Arc::new(Mutex::new(vec![]));
+        let handler = |seen: Seen, target: Arc<Mutex<String>>| {
+            move |req: MockRequest| {
+                let auth = req.header("authorization").map(|v| v.to_string());
+                seen.lock().unwrap().push((req.path.clone(), auth));
+                let target = target.lock().unwrap().clone();
+                match req.path.as_str() {
+                    "/same" => MockResponse::new(302).header("Location", "/file"),
+                    "/cross" => MockResponse::new(302)
+                        .header("Location", format!("{}/other", target).as_str()),
+                    "/bounce" => MockResponse::new(302)
+                        .header("Location", format!("{}/back", target).as_str()),
+                    "/back" => MockResponse::new(302)
+                        .header("Location", format!("{}/file", target).as_str()),
+                    _ => MockResponse::new(200).body("abcd"),
+                }
+            }
+        };
+        let first_target = Arc::new(Mutex::new(String::new()));
+        let second_target = Arc::new(Mutex::new(String::new()));
+        let first = serve(handler(seen.clone(), first_target.clone()));
+        let second = serve(handler(seen.clone(), second_target.clone()));
+        *first_target.lock().unwrap() = second.clone();
+        *second_target.lock().unwrap() = first.clone();
+
+        let auth_token = AuthToken::empty().header("Authorization", "Bearer t");
+        for testcase in testcases {
+            seen.lock().unwrap().clear();
+            let uri = format!("{}{}", first, testcase.path);
+            let mut reader =
+                super::download_file(uri.as_str(), &DownloadSetting::default(), &auth_token, 0)
+                    .await?;
+            let mut data = String::new();
+            reader.read_to_string(&mut data).await?;
+            assert_eq!(data, "abcd");
+
+            let expected: Vec<(String, Option<String>)> = testcase
+                .seen
+                .iter()
+                .map(|(path, auth)| (path.to_string(), auth.map(|v| v.to_string())))
+                .collect();
+            assert_eq!(*seen.lock().unwrap(), expected);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::{anyhow, Error, Result};
+use surf::Url;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct File {
+    url: String,
+}
+
+impl File {
+    pub fn get_url(&self) -> &str {
+        self.url.as_str()
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        let url = Url::parse(src)?;
+        match url.scheme() {
+            "http" | "https" => Ok(File {
+                url: url.to_string(),
+            }),
+            scheme => Err(anyhow!("unable to recognize the scheme {}.", scheme)),
+        }
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+impl From<File> for String {
+    fn from(file: File) -> String {
+        file.url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_tryfrom_str() -> Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            url: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "https://example.com/a/b.txt?c=d",
+                url: Some("https://example.com/a/b.txt?c=d"),
+            },
+            TestCase {
+                src: "http://example.com",
+                url: Some("http://example.com/"),
+            },
+            TestCase {
+                src: "ftp://example.com/a.txt",
+                url: None,
+            },
+            TestCase {
+                src: "example.com/a.txt",
+                url: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let file = File::try_from(testcase.src);
+            match testcase.url {
+                Some(v) => assert_eq!(file?.get_url(), v),
+                None => assert!(file.is_err()),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod download;
+use download::*;
+
+use crate::{Download, DownloadRange, FileMetadata, LinkStatus, Metadata, Probe, Service};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+
+pub struct Http {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DownloadSetting {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub max_redirects: usize,
+}
+
+impl Default for DownloadSetting {
+    fn default() -> DownloadSetting {
+        DownloadSetting {
+            etag: None,
+            last_modified: None,
+            max_redirects: 10,
+        }
+    }
+}
+
+impl DownloadSetting {
+    pub async fn pinned(file: &File, auth_token: &AuthToken) -> anyhow::Result<DownloadSetting> {
+        let setting = DownloadSetting::default();
+        let (_, etag, last_modified) =
+            file_metadata(file.get_url(), auth_token, setting.max_redirects).await?;
+        Ok(DownloadSetting {
+            etag,
+            last_modified,
+            ..setting
+        })
+    }
+}
+
+impl Service for Http {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for Http {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(file.get_url(), &setting, auth_token, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Http {
+    async fn download_range<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(file.get_url(), &setting, auth_token, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for Http {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        let setting = DownloadSetting::default();
+        let (metadata, _, _) =
+            file_metadata(file.get_url(), auth_token, setting.max_redirects).await?;
+        Ok(metadata)
+    }
+}
+
+#[async_trait]
+impl Probe for Http {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        let setting = DownloadSetting::default();
+        file_status(file.get_url(), auth_token, setting.max_redirects).await
+    }
+}
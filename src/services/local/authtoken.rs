@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthToken {
+    pub root: PathBuf,
+}
+
+impl AuthToken {
+    pub fn new<P: Into<PathBuf>>(root: P) -> AuthToken {
+        AuthToken { root: root.into() }
+    }
+}
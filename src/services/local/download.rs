@@ -0,0 +1,44 @@
+use crate::{FileMetadata, LinkStatus};
+use async_std::fs;
+use futures::io::BufReader;
+use futures::{AsyncBufRead, AsyncSeekExt};
+use std::io;
+use std::path::Path;
+
+pub async fn download_file<'a>(
+    root: &'a Path,
+    path: &'a Path,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let mut file = fs::File::open(root.join(path)).await?;
+    if offset > 0 {
+        file.seek(io::SeekFrom::Start(offset as u64)).await?;
+    }
+
+    Ok(Box::new(BufReader::new(file)))
+}
+
+pub async fn file_metadata<'a>(root: &'a Path, path: &'a Path) -> anyhow::Result<FileMetadata> {
+    let metadata = fs::metadata(root.join(path)).await?;
+    if !metadata.is_file() {
+        return Err(anyhow::anyhow!("{} is not a file.", path.display()));
+    }
+    let name = match path.file_name() {
+        Some(v) => v.to_string_lossy().to_string(),
+        None => return Err(anyhow::anyhow!("{} is not a file.", path.display())),
+    };
+
+    Ok(FileMetadata {
+        name,
+        len: Some(metadata.len() as usize),
+    })
+}
+
+pub async fn file_status<'a>(root: &'a Path, path: &'a Path) -> anyhow::Result<LinkStatus> {
+    match fs::metadata(root.join(path)).await {
+        Ok(_) => Ok(LinkStatus::Alive(file_metadata(root, path).await?)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(LinkStatus::Removed),
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => Ok(LinkStatus::Private),
+        Err(e) => Ok(LinkStatus::Unknown(e.to_string())),
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::{anyhow, Error, Result};
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct File {
+    path: PathBuf,
+}
+
+impl File {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Result<File> {
+        let path = path.into();
+        let valid = path.components().count() > 0
+            && path.components().all(|v| matches!(v, Component::Normal(_)));
+        if !valid {
+            return Err(anyhow!("unable to recognize the path {}.", path.display()));
+        }
+        Ok(File { path })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        self.path.as_path()
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        match src.strip_prefix("local://") {
+            Some(v) => File::new(v),
+            None => Err(anyhow!("unable to recognize the id.")),
+        }
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+impl From<File> for String {
+    fn from(file: File) -> String {
+        let segments: Vec<String> = file
+            .path
+            .components()
+            .map(|v| v.as_os_str().to_string_lossy().to_string())
+            .collect();
+        format!("local://{}", segments.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_tryfrom_str() -> Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            path: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "local://a.txt",
+                path: Some("a.txt"),
+            },
+            TestCase {
+                src: "local://ab/abcdef/a.txt",
+                path: Some("ab/abcdef/a.txt"),
+            },
+            TestCase {
+                src: "local://../a.txt",
+                path: None,
+            },
+            TestCase {
+                src: "local:///etc/passwd",
+                path: None,
+            },
+            TestCase {
+                src: "local://",
+                path: None,
+            },
+            TestCase {
+                src: "https://example.com/a.txt",
+                path: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let file = File::try_from(testcase.src);
+            match testcase.path {
+                Some(v) => {
+                    let file = file?;
+                    assert_eq!(file.get_path(), Path::new(v));
+                    let uri: String = file.into();
+                    assert_eq!(uri, testcase.src);
+                }
+                None => assert!(file.is_err()),
+            }
+        }
+        Ok(())
+    }
+}
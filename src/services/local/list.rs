@@ -0,0 +1,39 @@
+use crate::FileMetadata;
+use async_std::fs;
+use futures::StreamExt;
+use std::path::{Path, PathBuf};
+
+pub async fn list_files(root: &Path) -> anyhow::Result<Vec<(PathBuf, FileMetadata)>> {
+    let mut files = vec![];
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(dir) = dirs.pop() {
+        let mut entries = match fs::read_dir(root.join(&dir)).await {
+            Ok(v) => v,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(".upload-") {
+                continue;
+            }
+            let path = dir.join(&name);
+            let metadata = entry.metadata().await?;
+            if metadata.is_dir() {
+                dirs.push(path);
+            } else if metadata.is_file() {
+                files.push((
+                    path,
+                    FileMetadata {
+                        name,
+                        len: Some(metadata.len() as usize),
+                    },
+                ));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(files)
+}
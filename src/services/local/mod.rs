@@ -0,0 +1,292 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod download;
+use download::*;
+mod list;
+use list::*;
+mod upload;
+use upload::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Service,
+    Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+
+pub struct Local {}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Layout {
+    Name,
+    ContentAddressed,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UploadSetting {
+    pub layout: Layout,
+}
+
+impl Service for Local {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for Local {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&auth_token.root, file.get_path(), 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Local {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&auth_token.root, file.get_path(), offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for Local {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        file_metadata(&auth_token.root, file.get_path()).await
+    }
+}
+
+#[async_trait]
+impl Probe for Local {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        file_status(&auth_token.root, file.get_path()).await
+    }
+}
+
+#[async_trait]
+impl Delete for Local {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        async_std::fs::remove_file(auth_token.root.join(file.get_path())).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl List for Local {
+    async fn list<'a>(
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Vec<(Self::File, FileMetadata)>> {
+        let mut files = vec![];
+        for (path, metadata) in list_files(&auth_token.root).await? {
+            files.push((File::new(path)?, metadata));
+        }
+        Ok(files)
+    }
+}
+
+#[async_trait]
+impl Upload for Local {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::MAX
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        _len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let path = upload_file(&auth_token.root, name, reader, setting.layout).await?;
+        File::new(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, DownloadSetting, Layout, Local, UploadSetting};
+    use crate::{
+        Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Upload,
+    };
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+
+    #[tokio::test]
+    async fn local_roundtrip() -> anyhow::Result<()> {
+        struct TestCase {
+            name: &'static str,
+            data: &'static str,
+            layout: Layout,
+            uri: Option<&'static str>,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "a.txt",
+                data: "abcd",
+                layout: Layout::Name,
+                uri: Some("local://a.txt"),
+            },
+            TestCase {
+                name: "a.txt",
+                data: "efgh",
+                layout: Layout::Name,
+                uri: Some("local://a (1).txt"),
+            },
+            TestCase {
+                name: "a.txt",
+                data: "abcd",
+                layout: Layout::ContentAddressed,
+                uri: Some("local://88/88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589/a.txt"),
+            },
+            TestCase {
+                name: "a.txt",
+                data: "abcd",
+                layout: Layout::ContentAddressed,
+                uri: Some("local://88/88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589/a.txt"),
+            },
+            TestCase {
+                name: "../a.txt",
+                data: "abcd",
+                layout: Layout::Name,
+                uri: None,
+            },
+        ];
+
+        let root = std::env::temp_dir().join(format!("hearthbeat-local-{}", rand::random::<u64>()));
+        let auth_token = AuthToken::new(&root);
+        for testcase in testcases {
+            let reader = Box::new(Cursor::new(testcase.data));
+            let setting = UploadSetting {
+                layout: testcase.layout,
+            };
+            let result = Local::upload(
+                testcase.name,
+                reader,
+                Some(testcase.data.len()),
+                setting,
+                &auth_token,
+            )
+            .await;
+            let file = match testcase.uri {
+                Some(v) => {
+                    let file = result?;
+                    assert_eq!(String::from(file.clone()), v);
+                    file
+                }
+                None => {
+                    assert!(result.is_err());
+                    continue;
+                }
+            };
+
+            let mut data = String::new();
+            Local::download(file.clone(), DownloadSetting {}, &auth_token)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, testcase.data);
+
+            let mut data = String::new();
+            Local::download_range(file.clone(), DownloadSetting {}, &auth_token, 2)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, &testcase.data[2..]);
+
+            let metadata = FileMetadata {
+                name: String::from(file.get_path().file_name().unwrap().to_string_lossy()),
+                len: Some(testcase.data.len()),
+            };
+            assert_eq!(Local::metadata(&file, &auth_token).await?, metadata);
+            assert_eq!(
+                Local::probe(&file, &auth_token).await?,
+                LinkStatus::Alive(metadata)
+            );
+        }
+
+        let listed: Vec<String> = Local::list(&auth_token)
+            .await?
+            .into_iter()
+            .map(|(file, _)| file.into())
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                "local://88/88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589/a.txt",
+                "local://a (1).txt",
+                "local://a.txt",
+            ]
+        );
+
+        let removed = super::File::new("a.txt")?;
+        Local::delete(removed.clone(), &auth_token).await?;
+        assert_eq!(
+            Local::probe(&removed, &auth_token).await?,
+            LinkStatus::Removed
+        );
+        assert_eq!(Local::list(&auth_token).await?.len(), 2);
+
+        std::fs::remove_dir_all(root)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn local_concurrent_upload() -> anyhow::Result<()> {
+        let root = std::env::temp_dir().join(format!("hearthbeat-local-{}", rand::random::<u64>()));
+        let auth_token = AuthToken::new(&root);
+
+        let uploads = (0..8).map(|i| {
+            let data = format!("{}", i);
+            let auth_token = &auth_token;
+            async move {
+                let reader = Box::new(Cursor::new(data.clone()));
+                let setting = UploadSetting {
+                    layout: Layout::Name,
+                };
+                let file =
+                    Local::upload("a.txt", reader, Some(data.len()), setting, auth_token).await?;
+                anyhow::Ok((file, data))
+            }
+        });
+        let uploaded = futures::future::try_join_all(uploads).await?;
+
+        let mut uris: Vec<String> = uploaded.iter().map(|(v, _)| v.clone().into()).collect();
+        uris.sort();
+        uris.dedup();
+        assert_eq!(uris.len(), 8);
+        for (file, data) in uploaded {
+            let mut read = String::new();
+            Local::download(file, DownloadSetting {}, &auth_token)
+                .await?
+                .read_to_string(&mut read)
+                .await?;
+            assert_eq!(read, data);
+        }
+        assert_eq!(Local::list(&auth_token).await?.len(), 8);
+
+        std::fs::remove_dir_all(root)?;
+        Ok(())
+    }
+}
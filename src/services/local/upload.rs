@@ -0,0 +1,92 @@
+use super::Layout;
+use crate::hash::{HashAlgorithm, Hasher};
+use async_std::fs;
+use futures::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt};
+use std::path::{Path, PathBuf};
+
+pub async fn upload_file<'a>(
+    root: &'a Path,
+    name: &'a str,
+    mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    layout: Layout,
+) -> anyhow::Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(anyhow::anyhow!("unable to recognize the name {}.", name));
+    }
+    fs::create_dir_all(root).await?;
+
+    let tmp = root.join(format!(".upload-{}", rand::random::<u64>()));
+    let result = async {
+        let mut writer = fs::File::create(&tmp).await?;
+        let mut hasher = Hasher::new(HashAlgorithm::Sha256);
+        loop {
+            let data = reader.fill_buf().await?;
+            if data.is_empty() {
+                break;
+            }
+            let n = data.len();
+            hasher.update(data);
+            writer.write_all(data).await?;
+            reader.consume_unpin(n);
+        }
+        writer.flush().await?;
+        writer.sync_all().await?;
+        anyhow::Ok(hasher.finalize())
+    }
+    .await;
+    let digest = match result {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(e);
+        }
+    };
+
+    let path = match layout {
+        Layout::Name => {
+            let claimed = claim_path(root, name, &tmp).await;
+            fs::remove_file(&tmp).await?;
+            return claimed;
+        }
+        Layout::ContentAddressed => {
+            let hex = digest.to_hex();
+            let path = PathBuf::from(&hex[..2]).join(&hex).join(name);
+            if fs::metadata(root.join(&path)).await.is_ok() {
+                fs::remove_file(&tmp).await?;
+                return Ok(path);
+            }
+            path
+        }
+    };
+
+    if let Some(parent) = root.join(&path).parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::rename(&tmp, root.join(&path)).await?;
+
+    Ok(path)
+}
+
+// Linking fails instead of replacing an existing file, so concurrent uploads
+// of the same name each claim a distinct path.
+async fn claim_path<'a>(root: &'a Path, name: &'a str, tmp: &'a Path) -> anyhow::Result<PathBuf> {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (name, None),
+    };
+
+    let mut path = PathBuf::from(name);
+    let mut n = 1;
+    loop {
+        match fs::hard_link(tmp, root.join(&path)).await {
+            Ok(()) => return Ok(path),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e.into()),
+        }
+        path = match extension {
+            Some(extension) => PathBuf::from(format!("{} ({}).{}", stem, n, extension)),
+            None => PathBuf::from(format!("{} ({})", stem, n)),
+        };
+        n += 1;
+    }
+}
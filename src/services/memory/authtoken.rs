@@ -0,0 +1,70 @@
+use super::File;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Faults {
+    pub drop_after: Option<usize>,
+    pub latency: Option<Duration>,
+    pub rate_limited: usize,
+    pub corrupt_at: Option<usize>,
+}
+
+pub(super) struct Stored {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Default)]
+pub(super) struct Store {
+    pub files: HashMap<File, Stored>,
+    pub faults: Faults,
+    pub uploads: usize,
+}
+
+#[derive(Clone, Default)]
+pub struct AuthToken {
+    pub(super) store: Arc<Mutex<Store>>,
+}
+
+impl AuthToken {
+    pub fn new() -> AuthToken {
+        AuthToken::default()
+    }
+
+    pub fn set_faults(&self, faults: Faults) {
+        self.store.lock().unwrap().faults = faults;
+    }
+
+    pub fn insert(&self, name: &str, data: &[u8]) -> File {
+        let mut store = self.store.lock().unwrap();
+        store.uploads += 1;
+        let file = File::new(format!("{}/{}", store.uploads, name).as_str());
+        store.files.insert(
+            file.clone(),
+            Stored {
+                name: name.to_string(),
+                data: data.to_vec(),
+            },
+        );
+        file
+    }
+
+    pub fn get(&self, file: &File) -> Option<Vec<u8>> {
+        let store = self.store.lock().unwrap();
+        store.files.get(file).map(|v| v.data.clone())
+    }
+
+    pub fn remove(&self, file: &File) -> bool {
+        self.store.lock().unwrap().files.remove(file).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.store.lock().unwrap().files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::{anyhow, Error, Result};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct File {
+    id: String,
+}
+
+impl File {
+    pub fn new(id: &str) -> File {
+        File { id: id.to_string() }
+    }
+
+    pub fn get_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        match src.strip_prefix("memory://") {
+            Some(v) if !v.is_empty() => Ok(File::new(v)),
+            _ => Err(anyhow!("unable to recognize the id.")),
+        }
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+impl From<File> for String {
+    fn from(file: File) -> String {
+        format!("memory://{}", file.id)
+    }
+}
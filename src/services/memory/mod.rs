@@ -0,0 +1,362 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Service,
+    Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use futures::{AsyncBufReadExt, TryStreamExt};
+use std::fmt;
+use std::io;
+
+pub struct Memory {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UploadSetting {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RateLimited {}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rate limited, retry later.")
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+async fn request(auth_token: &AuthToken) -> anyhow::Result<Faults> {
+    let (faults, limited) = {
+        let mut store = auth_token.store.lock().unwrap();
+        let faults = store.faults.clone();
+        let limited = store.faults.rate_limited > 0;
+        if limited {
+            store.faults.rate_limited -= 1;
+        }
+        (faults, limited)
+    };
+
+    if let Some(latency) = faults.latency {
+        async_std::task::sleep(latency).await;
+    }
+    if limited {
+        return Err(RateLimited {}.into());
+    }
+
+    Ok(faults)
+}
+
+impl Service for Memory {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for Memory {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        Memory::download_range(file, setting, auth_token, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Memory {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        let faults = request(auth_token).await?;
+        let data = match auth_token.get(&file) {
+            Some(v) => v,
+            None => return Err(anyhow::anyhow!("file does not exist.")),
+        };
+        if offset > data.len() {
+            return Err(anyhow::anyhow!("offset is larger than {}.", data.len()));
+        }
+
+        let mut data = data[offset..].to_vec();
+        if let Some(at) = faults.corrupt_at {
+            if at >= offset && at - offset < data.len() {
+                data[at - offset] ^= 0xff;
+            }
+        }
+        let chunks: Vec<io::Result<Vec<u8>>> = match faults.drop_after {
+            Some(n) if n < data.len() => vec![
+                Ok(data[..n].to_vec()),
+                Err(io::ErrorKind::ConnectionReset.into()),
+            ],
+            _ => vec![Ok(data)],
+        };
+
+        Ok(Box::new(futures::stream::iter(chunks).into_async_read()))
+    }
+}
+
+#[async_trait]
+impl Metadata for Memory {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        request(auth_token).await?;
+        let store = auth_token.store.lock().unwrap();
+        match store.files.get(file) {
+            Some(v) => Ok(FileMetadata {
+                name: v.name.clone(),
+                len: Some(v.data.len()),
+            }),
+            None => Err(anyhow::anyhow!("file does not exist.")),
+        }
+    }
+}
+
+#[async_trait]
+impl Probe for Memory {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        request(auth_token).await?;
+        let store = auth_token.store.lock().unwrap();
+        match store.files.get(file) {
+            Some(v) => Ok(LinkStatus::Alive(FileMetadata {
+                name: v.name.clone(),
+                len: Some(v.data.len()),
+            })),
+            None => Ok(LinkStatus::Removed),
+        }
+    }
+}
+
+#[async_trait]
+impl Delete for Memory {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        request(auth_token).await?;
+        match auth_token.remove(&file) {
+            true => Ok(()),
+            false => Err(anyhow::anyhow!("file does not exist.")),
+        }
+    }
+}
+
+#[async_trait]
+impl List for Memory {
+    async fn list<'a>(
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Vec<(Self::File, FileMetadata)>> {
+        request(auth_token).await?;
+        let store = auth_token.store.lock().unwrap();
+        let mut files: Vec<(File, FileMetadata)> = store
+            .files
+            .iter()
+            .map(|(file, v)| {
+                let metadata = FileMetadata {
+                    name: v.name.clone(),
+                    len: Some(v.data.len()),
+                };
+                (file.clone(), metadata)
+            })
+            .collect();
+        files.sort_by(|a, b| a.0.get_id().cmp(b.0.get_id()));
+        Ok(files)
+    }
+}
+
+#[async_trait]
+impl Upload for Memory {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::MAX
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        _len: Option<usize>,
+        _setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let faults = request(auth_token).await?;
+
+        let mut data = vec![];
+        loop {
+            let chunk = reader.fill_buf().await?;
+            if chunk.is_empty() {
+                break;
+            }
+            let n = chunk.len();
+            data.extend_from_slice(chunk);
+            reader.consume_unpin(n);
+
+            if let Some(drop_after) = faults.drop_after {
+                if data.len() > drop_after {
+                    return Err(io::Error::from(io::ErrorKind::ConnectionReset).into());
+                }
+            }
+        }
+
+        Ok(auth_token.insert(name, data.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, DownloadSetting, Faults, Memory, RateLimited, UploadSetting};
+    use crate::failover::Failover;
+    use crate::hash::{verify, Digest, HashAlgorithm, Hasher};
+    use crate::{Delete, Download, LinkStatus, List, Probe, Upload};
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn memory_faults() -> anyhow::Result<()> {
+        struct TestCase {
+            faults: Faults,
+            upload: bool,
+            download: Option<&'static [u8]>,
+            min: Duration,
+        }
+
+        let testcases = [
+            TestCase {
+                faults: Faults::default(),
+                upload: true,
+                download: Some(b"abcdef"),
+                min: Duration::ZERO,
+            },
+            TestCase {
+                faults: Faults {
+                    rate_limited: 1,
+                    ..Faults::default()
+                },
+                upload: false,
+                download: Some(b"abcdef"),
+                min: Duration::ZERO,
+            },
+            TestCase {
+                faults: Faults {
+                    drop_after: Some(4),
+                    ..Faults::default()
+                },
+                upload: false,
+                download: None,
+                min: Duration::ZERO,
+            },
+            TestCase {
+                faults: Faults {
+                    corrupt_at: Some(1),
+                    ..Faults::default()
+                },
+                upload: true,
+                download: Some(b"a\x9dcdef"),
+                min: Duration::ZERO,
+            },
+            TestCase {
+                faults: Faults {
+                    latency: Some(Duration::from_millis(30)),
+                    ..Faults::default()
+                },
+                upload: true,
+                download: Some(b"abcdef"),
+                min: Duration::from_millis(60),
+            },
+        ];
+
+        for testcase in testcases {
+            let auth_token = AuthToken::new();
+            let stored = auth_token.insert("a.txt", b"abcdef");
+            let limited = testcase.faults.rate_limited > 0;
+            auth_token.set_faults(testcase.faults);
+
+            let start = Instant::now();
+            let reader = Box::new(Cursor::new("abcdef"));
+            let result =
+                Memory::upload("b.txt", reader, Some(6), UploadSetting {}, &auth_token).await;
+            assert_eq!(result.is_ok(), testcase.upload);
+            if let Err(e) = result {
+                assert_eq!(e.downcast_ref::<RateLimited>().is_some(), limited);
+            }
+
+            let result = async {
+                let mut data = vec![];
+                Memory::download(stored.clone(), DownloadSetting {}, &auth_token)
+                    .await?
+                    .read_to_end(&mut data)
+                    .await?;
+                anyhow::Ok(data)
+            }
+            .await;
+            match testcase.download {
+                Some(v) => assert_eq!(result?, v),
+                None => assert!(result.is_err()),
+            }
+            assert!(start.elapsed() >= testcase.min);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn memory_resume_and_verify() -> anyhow::Result<()> {
+        let auth_token = AuthToken::new();
+        let file = auth_token.insert("a.txt", b"abcdef");
+
+        auth_token.set_faults(Faults {
+            drop_after: Some(4),
+            ..Faults::default()
+        });
+        let mut data = String::new();
+        Failover::new()
+            .range_source::<Memory>(file.clone(), DownloadSetting {}, auth_token.clone())
+            .range_source::<Memory>(file.clone(), DownloadSetting {}, auth_token.clone())
+            .download()
+            .read_to_string(&mut data)
+            .await?;
+        assert_eq!(data, "abcdef");
+
+        auth_token.set_faults(Faults {
+            corrupt_at: Some(2),
+            ..Faults::default()
+        });
+        let digest: Digest = {
+            let mut hasher = Hasher::new(HashAlgorithm::Sha256);
+            hasher.update(b"abcdef");
+            hasher.finalize()
+        };
+        let reader = Memory::download(file.clone(), DownloadSetting {}, &auth_token).await?;
+        let mut data = vec![];
+        assert!(verify(reader, digest).read_to_end(&mut data).await.is_err());
+
+        let listed: Vec<String> = Memory::list(&auth_token)
+            .await?
+            .into_iter()
+            .map(|(file, _)| file.into())
+            .collect();
+        assert_eq!(listed, vec![String::from(file.clone())]);
+
+        Memory::delete(file.clone(), &auth_token).await?;
+        assert!(Memory::delete(file.clone(), &auth_token).await.is_err());
+        assert_eq!(
+            Memory::probe(&file, &auth_token).await?,
+            LinkStatus::Removed
+        );
+
+        Ok(())
+    }
+}
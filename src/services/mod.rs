@@ -1,2 +1,13 @@
+pub mod ftp;
+pub mod gofile;
+pub mod http;
+pub mod local;
+pub mod memory;
+pub mod nullpointer;
+pub mod pixeldrain;
+pub mod s3;
+pub mod sftp;
+pub mod transfersh;
+pub mod webdav;
 mod zippyshare;
 pub use zippyshare::*;
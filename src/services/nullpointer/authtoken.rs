@@ -0,0 +1,31 @@
+use anyhow::{anyhow, Result};
+use surf::Url;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthToken {
+    pub base: Url,
+    pub max_size: usize,
+}
+
+impl AuthToken {
+    pub fn new(base: &str) -> Result<AuthToken> {
+        let mut base = Url::parse(base)?;
+        if !matches!(base.scheme(), "http" | "https") {
+            return Err(anyhow!("unable to recognize the scheme {}.", base.scheme()));
+        }
+        if !base.path().ends_with('/') {
+            let path = format!("{}/", base.path());
+            base.set_path(path.as_str());
+        }
+
+        Ok(AuthToken {
+            base,
+            max_size: 512 * 1024 * 1024,
+        })
+    }
+
+    pub fn max_size(mut self, max_size: usize) -> AuthToken {
+        self.max_size = max_size;
+        self
+    }
+}
@@ -0,0 +1,101 @@
+use super::File;
+use crate::utils::{
+    gen_boundary, percent_decode, send, skip, Multipart, MultipartContentEnum, MultipartField,
+    BOUNDARY_CHARSET,
+};
+use crate::FileMetadata;
+use futures::AsyncBufRead;
+use surf::http::{Method, StatusCode};
+use surf::Client;
+
+pub async fn download_file(
+    file: &File,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let client = Client::new();
+
+    let mut headers = vec![];
+    if offset > 0 {
+        headers.push(("Range", format!("bytes={}-", offset)));
+    }
+    let mut res = send(&client, Method::Get, file.get_url(), headers, None, |req| {
+        req
+    })
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    let mut reader = res.take_body().into_reader();
+    if offset > 0 && res.status() != StatusCode::PartialContent {
+        skip(&mut reader, offset).await?;
+    }
+
+    Ok(reader)
+}
+
+pub async fn probe_file(file: &File) -> anyhow::Result<(StatusCode, Option<FileMetadata>)> {
+    let client = Client::new();
+
+    let headers = vec![("Range", String::from("bytes=0-0"))];
+    let res = send(&client, Method::Get, file.get_url(), headers, None, |req| {
+        req
+    })
+    .await?;
+    let len = match res.status() {
+        StatusCode::PartialContent => res
+            .header("Content-Range")
+            .and_then(|v| v.last().as_str().rsplit_once('/'))
+            .and_then(|(_, v)| v.trim().parse().ok()),
+        v if v.is_success() => res
+            .header("Content-Length")
+            .and_then(|v| v.last().as_str().parse().ok()),
+        v => return Ok((v, None)),
+    };
+    let name = file
+        .get_url()
+        .path_segments()
+        .and_then(|mut v| v.next_back())
+        .and_then(percent_decode)
+        .unwrap_or_default();
+
+    Ok((res.status(), Some(FileMetadata { name, len })))
+}
+
+pub async fn delete_file(file: &File) -> anyhow::Result<()> {
+    let client = Client::new();
+
+    let token = match file.get_token() {
+        Some(v) => v,
+        None => return Err(anyhow::anyhow!("management token is missing.")),
+    };
+    let boundary = gen_boundary(16, BOUNDARY_CHARSET)?;
+    let body = Multipart::new(MultipartField {
+        name: "token",
+        data: MultipartContentEnum::String(token.to_string()),
+    })
+    .chain(MultipartField {
+        name: "delete",
+        data: MultipartContentEnum::Str(""),
+    })
+    .into_body(boundary.as_str());
+    let headers = vec![(
+        "Content-Type",
+        format!("multipart/form-data; boundary={}", boundary),
+    )];
+
+    let res = send(
+        &client,
+        Method::Post,
+        file.get_url(),
+        headers,
+        Some(body),
+        |req| req,
+    )
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,274 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod download;
+use download::*;
+mod upload;
+use upload::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, Metadata, Probe, Service, Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use surf::http::StatusCode;
+
+pub struct NullPointer {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expires {
+    Hours(u32),
+    Timestamp(u64),
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UploadSetting {
+    pub secret: bool,
+    pub expires: Option<Expires>,
+}
+
+impl Service for NullPointer {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for NullPointer {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        _auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for NullPointer {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        _auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for NullPointer {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        _auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        match probe_file(file).await? {
+            (_, Some(metadata)) => Ok(metadata),
+            (status, None) => Err(anyhow::anyhow!("unexpected status {}.", status)),
+        }
+    }
+}
+
+#[async_trait]
+impl Probe for NullPointer {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        _auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        let status = match probe_file(file).await? {
+            (_, Some(metadata)) => LinkStatus::Alive(metadata),
+            (StatusCode::NotFound | StatusCode::Gone, None) => LinkStatus::Removed,
+            (StatusCode::UnavailableForLegalReasons, None) => LinkStatus::Removed,
+            (StatusCode::Unauthorized | StatusCode::Forbidden, None) => LinkStatus::Private,
+            (v, None) => LinkStatus::Unknown(format!("unexpected status {}.", v)),
+        };
+
+        Ok(status)
+    }
+}
+
+#[async_trait]
+impl Delete for NullPointer {
+    async fn delete<'a>(file: Self::File, _auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        delete_file(&file).await
+    }
+}
+
+#[async_trait]
+impl Upload for NullPointer {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(auth_token: &Self::AuthToken) -> usize {
+        auth_token.max_size
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        upload_file(name, reader, len, &setting, auth_token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, DownloadSetting, Expires, NullPointer, UploadSetting};
+    use crate::utils::{roundtrip, serve_with_base, MockResponse};
+    use crate::{Delete, FileMetadata, Upload};
+    use async_std::io::Cursor;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    struct Stored {
+        data: Vec<u8>,
+        token: String,
+        expires: Option<String>,
+    }
+
+    fn server(store: Arc<Mutex<BTreeMap<String, Stored>>>) -> String {
+        let count = AtomicUsize::new(0);
+        serve_with_base(move |base, req| {
+            let mut store = store.lock().unwrap();
+            match (req.method.as_str(), req.path.as_str()) {
+                ("POST", "/") => {
+                    let form = req.form();
+                    let (filename, data) = match form.get("file") {
+                        Some((Some(filename), data)) => (filename, data),
+                        _ => return MockResponse::new(400),
+                    };
+                    let ext = filename.rsplit_once('.').map(|(_, v)| v).unwrap_or("bin");
+                    let id = count.fetch_add(1, Ordering::SeqCst);
+                    let path = match form.contains_key("secret") {
+                        true => format!("/s/Sec{}/F{}.{}", id, id, ext),
+                        false => format!("/F{}.{}", id, ext),
+                    };
+                    let token = format!("tok{}", id);
+                    store.insert(
+                        path.clone(),
+                        Stored {
+                            data: data.clone(),
+                            token: token.clone(),
+                            expires: form
+                                .get("expires")
+                                .map(|(_, v)| String::from_utf8_lossy(v).to_string()),
+                        },
+                    );
+                    MockResponse::new(200)
+                        .header("X-Token", token.as_str())
+                        .body(format!("{}{}\n", base, path))
+                }
+                ("POST", path) => {
+                    let form = req.form();
+                    let token = form.get("token").map(|(_, v)| v.as_slice());
+                    match store.get(path) {
+                        Some(v) if token == Some(v.token.as_bytes()) => {}
+                        Some(_) => return MockResponse::new(401),
+                        None => return MockResponse::new(404),
+                    }
+                    if form.contains_key("delete") {
+                        store.remove(path);
+                    }
+                    MockResponse::new(200)
+                }
+                ("GET", path) => match store.get(path) {
+                    Some(v) => MockResponse::ranged(&req, &v.data),
+                    None => MockResponse::new(404),
+                },
+                _ => MockResponse::new(405),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn nullpointer_roundtrip() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            name: &'a str,
+            setting: UploadSetting,
+            path: &'a str,
+            expires: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "a.txt",
+                setting: UploadSetting::default(),
+                path: "/F0.txt",
+                expires: None,
+            },
+            TestCase {
+                name: "b.bin",
+                setting: UploadSetting {
+                    secret: true,
+                    expires: Some(Expires::Hours(24)),
+                },
+                path: "/s/Sec1/F1.bin",
+                expires: Some("24"),
+            },
+            TestCase {
+                name: "c.txt",
+                setting: UploadSetting {
+                    secret: false,
+                    expires: Some(Expires::Timestamp(1700000000000)),
+                },
+                path: "/F2.txt",
+                expires: Some("1700000000000"),
+            },
+        ];
+
+        let store = Arc::new(Mutex::new(BTreeMap::new()));
+        let base = server(store.clone());
+        let auth_token = AuthToken::new(base.as_str())?;
+        for testcase in testcases {
+            let reader = Box::new(Cursor::new("abcdefgh"));
+            let file = NullPointer::upload(
+                testcase.name,
+                reader,
+                Some(8),
+                testcase.setting,
+                &auth_token,
+            )
+            .await?;
+            assert_eq!(file.get_url().path(), testcase.path);
+            assert!(file.get_token().is_some());
+            {
+                let store = store.lock().unwrap();
+                let stored = store.get(testcase.path).unwrap();
+                assert_eq!(stored.data, b"abcdefgh");
+                assert_eq!(stored.expires.as_deref(), testcase.expires);
+            }
+
+            let forged = super::File::new(file.get_url().clone(), Some(String::from("wrong")));
+            assert!(NullPointer::delete(forged, &auth_token).await.is_err());
+
+            let metadata = FileMetadata {
+                name: testcase.path.rsplit('/').next().unwrap().to_string(),
+                len: Some(8),
+            };
+            roundtrip::<NullPointer>(file, DownloadSetting {}, &auth_token, b"abcdefgh", metadata)
+                .await?;
+        }
+
+        let file = super::File::try_from(format!("{}/F9.txt", base))?;
+        assert!(NullPointer::delete(file, &auth_token).await.is_err());
+
+        let auth_token = auth_token.max_size(4);
+        let reader = Box::new(Cursor::new("abcdefgh"));
+        let setting = UploadSetting::default();
+        assert!(
+            NullPointer::upload("d.txt", reader, Some(8), setting, &auth_token)
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use super::{AuthToken, Expires, File, UploadSetting};
+use crate::utils::{
+    gen_boundary, send, Multipart, MultipartContentEnum, MultipartField, BOUNDARY_CHARSET,
+};
+use futures::AsyncBufRead;
+use surf::http::Method;
+use surf::{Client, Url};
+
+pub async fn upload_file<'a>(
+    name: &'a str,
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    len: Option<usize>,
+    setting: &'a UploadSetting,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<File> {
+    let client = Client::new();
+
+    if let Some(v) = len {
+        if v > auth_token.max_size {
+            return Err(anyhow::anyhow!(
+                "file size {} exceeds the limit {}.",
+                v,
+                auth_token.max_size
+            ));
+        }
+    }
+
+    let boundary = gen_boundary(16, BOUNDARY_CHARSET)?;
+    let content_type = format!("multipart/form-data; boundary={}", boundary);
+    let body = {
+        let mut multipart = Multipart::new(MultipartField {
+            name: "file",
+            data: MultipartContentEnum::Reader(reader, name, len),
+        });
+        if setting.secret {
+            multipart = multipart.chain(MultipartField {
+                name: "secret",
+                data: MultipartContentEnum::Str(""),
+            });
+        }
+        if let Some(v) = setting.expires.as_ref() {
+            let expires = match v {
+                Expires::Hours(v) => v.to_string(),
+                Expires::Timestamp(v) => v.to_string(),
+            };
+            multipart = multipart.chain(MultipartField {
+                name: "expires",
+                data: MultipartContentEnum::String(expires),
+            });
+        }
+        multipart.into_body(boundary.as_str())
+    };
+
+    let headers = vec![("Content-Type", content_type)];
+    let mut res = send(
+        &client,
+        Method::Post,
+        &auth_token.base,
+        headers,
+        Some(body),
+        |req| req,
+    )
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    let token = res.header("X-Token").map(|v| v.last().to_string());
+    let problem = match res.body_string().await {
+        Ok(v) => v,
+        Err(e) => return Err(e.into_inner()),
+    };
+    let url = Url::parse(problem.trim())?;
+
+    Ok(File::new(url, token))
+}
@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use surf::{RequestBuilder, Url};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthToken {
+    pub base: Url,
+    pub api_key: Option<String>,
+}
+
+impl AuthToken {
+    pub fn new(api_key: &str) -> AuthToken {
+        AuthToken {
+            base: Url::parse("https://pixeldrain.com/api/").unwrap(),
+            api_key: Some(api_key.to_string()),
+        }
+    }
+
+    pub fn anonymous() -> AuthToken {
+        AuthToken {
+            base: Url::parse("https://pixeldrain.com/api/").unwrap(),
+            api_key: None,
+        }
+    }
+
+    pub fn endpoint(mut self, base: &str) -> Result<AuthToken> {
+        let mut base = Url::parse(base)?;
+        if !matches!(base.scheme(), "http" | "https") {
+            return Err(anyhow!("unable to recognize the scheme {}.", base.scheme()));
+        }
+        if !base.path().ends_with('/') {
+            let path = format!("{}/", base.path());
+            base.set_path(path.as_str());
+        }
+        self.base = base;
+        Ok(self)
+    }
+
+    pub fn authorization(&self) -> Option<String> {
+        self.api_key.as_ref().map(|v| {
+            let encoded = STANDARD.encode(format!(":{}", v));
+            format!("Basic {}", encoded)
+        })
+    }
+
+    pub(super) fn authorize(&self, req: RequestBuilder) -> RequestBuilder {
+        match self.authorization() {
+            Some(v) => req.header("Authorization", v),
+            None => req,
+        }
+    }
+
+    pub fn api_url(&self, segments: &[&str]) -> Result<Url> {
+        let mut url = self.base.clone();
+        match url.path_segments_mut() {
+            Ok(mut v) => {
+                v.pop_if_empty().extend(segments);
+            }
+            Err(_) => return Err(anyhow!("unable to recognize the base url.")),
+        }
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authtoken_api_url() -> Result<()> {
+        struct TestCase<'a> {
+            base: Option<&'a str>,
+            segments: &'a [&'a str],
+            url: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                base: None,
+                segments: &["file", "Ab12", "info"],
+                url: "https://pixeldrain.com/api/file/Ab12/info",
+            },
+            TestCase {
+                base: Some("http://127.0.0.1:8080/api"),
+                segments: &["file", "a b.txt"],
+                url: "http://127.0.0.1:8080/api/file/a%20b.txt",
+            },
+        ];
+
+        for testcase in testcases {
+            let mut auth_token = AuthToken::new("key");
+            if let Some(v) = testcase.base {
+                auth_token = auth_token.endpoint(v)?;
+            }
+            let url = auth_token.api_url(testcase.segments)?;
+            assert_eq!(url.as_str(), testcase.url);
+        }
+        assert_eq!(
+            AuthToken::new("key").authorization().as_deref(),
+            Some("Basic OmtleQ==")
+        );
+        assert_eq!(AuthToken::anonymous().authorization(), None);
+        Ok(())
+    }
+}
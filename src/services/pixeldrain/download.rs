@@ -0,0 +1,63 @@
+use super::{check, read_json, AuthToken, File, FileInfo};
+use crate::utils::{send, skip};
+use crate::FileMetadata;
+use futures::AsyncBufRead;
+use surf::http::{Method, StatusCode};
+use surf::Client;
+
+pub async fn download_file<'a>(
+    file: &'a File,
+    auth_token: &'a AuthToken,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let client = Client::new();
+
+    let mut url = auth_token.api_url(&["file", file.get_id()])?;
+    url.set_query(Some("download"));
+    let mut headers = vec![];
+    if offset > 0 {
+        headers.push(("Range", format!("bytes={}-", offset)));
+    }
+    let mut res = send(&client, Method::Get, &url, headers, None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    check(&mut res).await?;
+
+    let mut reader = res.take_body().into_reader();
+    if offset > 0 && res.status() != StatusCode::PartialContent {
+        skip(&mut reader, offset).await?;
+    }
+
+    Ok(reader)
+}
+
+pub async fn probe_file<'a>(
+    file: &'a File,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<(StatusCode, Option<FileMetadata>)> {
+    let client = Client::new();
+
+    let url = auth_token.api_url(&["file", file.get_id(), "info"])?;
+    let mut res = send(&client, Method::Get, &url, vec![], None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    if !res.status().is_success() {
+        return Ok((res.status(), None));
+    }
+    let info: FileInfo = read_json(&mut res).await?;
+
+    Ok((res.status(), Some(FileMetadata::from(&info))))
+}
+
+pub async fn delete_file<'a>(file: &'a File, auth_token: &'a AuthToken) -> anyhow::Result<()> {
+    let client = Client::new();
+
+    let url = auth_token.api_url(&["file", file.get_id()])?;
+    let mut res = send(&client, Method::Delete, &url, vec![], None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    check(&mut res).await
+}
@@ -0,0 +1,171 @@
+use anyhow::{anyhow, Error, Result};
+use regex::Regex;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct File {
+    id: String,
+}
+
+impl File {
+    pub fn new(id: &str) -> File {
+        File { id: id.to_string() }
+    }
+
+    pub fn get_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        let re = Regex::new(r"^https?://pixeldrain\.com/(?:u|api/file)/([\w\-]+)/?$").unwrap();
+        let cap = match re.captures(src) {
+            Some(v) => v,
+            None => return Err(anyhow!("unable to recognize the id.")),
+        };
+
+        Ok(File::new(&cap[1]))
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+impl From<File> for String {
+    fn from(file: File) -> String {
+        format!("https://pixeldrain.com/u/{}", file.id)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FileList {
+    id: String,
+}
+
+impl FileList {
+    pub fn new(id: &str) -> FileList {
+        FileList { id: id.to_string() }
+    }
+
+    pub fn get_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+impl TryFrom<&str> for FileList {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        let re = Regex::new(r"^https?://pixeldrain\.com/(?:l|api/list)/([\w\-]+)/?$").unwrap();
+        let cap = match re.captures(src) {
+            Some(v) => v,
+            None => return Err(anyhow!("unable to recognize the id.")),
+        };
+
+        Ok(FileList::new(&cap[1]))
+    }
+}
+
+impl TryFrom<String> for FileList {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        FileList::try_from(src.as_str())
+    }
+}
+
+impl From<FileList> for String {
+    fn from(list: FileList) -> String {
+        format!("https://pixeldrain.com/l/{}", list.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_tryfrom_str() -> Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            id: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "https://pixeldrain.com/u/Ab12Cd34",
+                id: Some("Ab12Cd34"),
+            },
+            TestCase {
+                src: "https://pixeldrain.com/api/file/Ab12Cd34",
+                id: Some("Ab12Cd34"),
+            },
+            TestCase {
+                src: "https://pixeldrain.com/l/Ab12Cd34",
+                id: None,
+            },
+            TestCase {
+                src: "https://example.com/u/Ab12Cd34",
+                id: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let file = File::try_from(testcase.src);
+            match testcase.id {
+                Some(v) => {
+                    let file = file?;
+                    assert_eq!(file.get_id(), v);
+                    let uri: String = file.into();
+                    assert_eq!(uri, format!("https://pixeldrain.com/u/{}", v));
+                }
+                None => assert!(file.is_err()),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn filelist_tryfrom_str() -> Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            id: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "https://pixeldrain.com/l/Xy98",
+                id: Some("Xy98"),
+            },
+            TestCase {
+                src: "https://pixeldrain.com/api/list/Xy98",
+                id: Some("Xy98"),
+            },
+            TestCase {
+                src: "https://pixeldrain.com/u/Xy98",
+                id: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let list = FileList::try_from(testcase.src);
+            match testcase.id {
+                Some(v) => {
+                    let list = list?;
+                    assert_eq!(list.get_id(), v);
+                    let uri: String = list.into();
+                    assert_eq!(uri, format!("https://pixeldrain.com/l/{}", v));
+                }
+                None => assert!(list.is_err()),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use super::{
+    read_json, AuthToken, CreateListRequest, File, FileList, FilesResponse, IdResponse, ListItem,
+};
+use crate::utils::send;
+use crate::FileMetadata;
+use surf::http::Method;
+use surf::{Body, Client};
+
+fn into_files(res: FilesResponse) -> Vec<(File, FileMetadata)> {
+    res.files
+        .iter()
+        .map(|v| (File::new(v.id.as_str()), FileMetadata::from(v)))
+        .collect()
+}
+
+pub async fn user_files(auth_token: &AuthToken) -> anyhow::Result<Vec<(File, FileMetadata)>> {
+    let client = Client::new();
+
+    if auth_token.api_key.is_none() {
+        return Err(anyhow::anyhow!("api key is required to list files."));
+    }
+    let url = auth_token.api_url(&["user", "files"])?;
+    let mut res = send(&client, Method::Get, &url, vec![], None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    let files: FilesResponse = read_json(&mut res).await?;
+
+    Ok(into_files(files))
+}
+
+pub async fn create_list<'a>(
+    title: &'a str,
+    files: &'a [File],
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<FileList> {
+    let client = Client::new();
+
+    let req = CreateListRequest {
+        title,
+        anonymous: auth_token.api_key.is_none(),
+        files: files
+            .iter()
+            .map(|v| ListItem {
+                id: v.get_id(),
+                description: "",
+            })
+            .collect(),
+    };
+    let body = Body::from_bytes(serde_json::to_vec(&req)?);
+    let headers = vec![("Content-Type", String::from("application/json"))];
+    let url = auth_token.api_url(&["list"])?;
+    let mut res = send(&client, Method::Post, &url, headers, Some(body), |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    let created: IdResponse = read_json(&mut res).await?;
+
+    Ok(FileList::new(created.id.as_str()))
+}
+
+pub async fn list_files<'a>(
+    list: &'a FileList,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<Vec<(File, FileMetadata)>> {
+    let client = Client::new();
+
+    let url = auth_token.api_url(&["list", list.get_id()])?;
+    let mut res = send(&client, Method::Get, &url, vec![], None, |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    let files: FilesResponse = read_json(&mut res).await?;
+
+    Ok(into_files(files))
+}
@@ -0,0 +1,404 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod request;
+use request::*;
+mod download;
+use download::*;
+mod list;
+use list::*;
+mod upload;
+use upload::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Service,
+    Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use surf::http::StatusCode;
+
+pub struct Pixeldrain {}
+
+impl Pixeldrain {
+    pub async fn create_list(
+        title: &str,
+        files: &[File],
+        auth_token: &AuthToken,
+    ) -> anyhow::Result<FileList> {
+        create_list(title, files, auth_token).await
+    }
+
+    pub async fn list_files(
+        list: &FileList,
+        auth_token: &AuthToken,
+    ) -> anyhow::Result<Vec<(File, FileMetadata)>> {
+        list_files(list, auth_token).await
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UploadSetting {}
+
+impl Service for Pixeldrain {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for Pixeldrain {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, auth_token, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Pixeldrain {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, auth_token, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for Pixeldrain {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        match probe_file(file, auth_token).await? {
+            (_, Some(metadata)) => Ok(metadata),
+            (status, None) => Err(anyhow::anyhow!("unexpected status {}.", status)),
+        }
+    }
+}
+
+#[async_trait]
+impl Probe for Pixeldrain {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        let status = match probe_file(file, auth_token).await? {
+            (_, Some(metadata)) => LinkStatus::Alive(metadata),
+            (StatusCode::NotFound | StatusCode::Gone, None) => LinkStatus::Removed,
+            (StatusCode::UnavailableForLegalReasons, None) => LinkStatus::Removed,
+            (StatusCode::Unauthorized | StatusCode::Forbidden, None) => LinkStatus::Private,
+            (v, None) => LinkStatus::Unknown(format!("unexpected status {}.", v)),
+        };
+
+        Ok(status)
+    }
+}
+
+#[async_trait]
+impl Delete for Pixeldrain {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        delete_file(&file, auth_token).await
+    }
+}
+
+#[async_trait]
+impl List for Pixeldrain {
+    async fn list<'a>(
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Vec<(Self::File, FileMetadata)>> {
+        user_files(auth_token).await
+    }
+}
+
+#[async_trait]
+impl Upload for Pixeldrain {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::try_from(20_000_000_000u64).unwrap_or(usize::MAX)
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        _setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        upload_file(name, reader, len, auth_token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, DownloadSetting, File, FileList, Pixeldrain, UploadSetting};
+    use crate::utils::{percent_decode, serve, MockResponse};
+    use crate::{
+        Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Upload,
+    };
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    struct Stored {
+        name: String,
+        data: Vec<u8>,
+        owner: Option<String>,
+    }
+
+    #[derive(Default)]
+    struct Store {
+        files: BTreeMap<String, Stored>,
+        lists: BTreeMap<String, Vec<String>>,
+    }
+
+    fn error(status: u16, value: &str) -> MockResponse {
+        let body = json!({"success": false, "value": value, "message": value});
+        MockResponse::new(status).body(body.to_string())
+    }
+
+    fn info(id: &str, stored: &Stored) -> Value {
+        json!({
+            "id": id,
+            "name": stored.name,
+            "size": stored.data.len(),
+            "views": 0,
+            "mime_type": "application/octet-stream",
+            "can_edit": true,
+        })
+    }
+
+    fn server(store: Arc<Mutex<Store>>) -> String {
+        let count = AtomicUsize::new(0);
+        serve(move |req| {
+            let mut store = store.lock().unwrap();
+            let owner = match req.header("authorization") {
+                Some("Basic OmtleQ==") => Some(String::from("key")),
+                Some(_) => return error(401, "unauthorized"),
+                None => None,
+            };
+            let (path, query) = req.path.split_once('?').unwrap_or((req.path.as_str(), ""));
+            let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+            let mut created = |name: String, data: Vec<u8>| {
+                let id = format!("F{}", count.fetch_add(1, Ordering::SeqCst));
+                store.files.insert(
+                    id.clone(),
+                    Stored {
+                        name,
+                        data,
+                        owner: owner.clone(),
+                    },
+                );
+                MockResponse::new(201).body(json!({ "id": id }).to_string())
+            };
+            match (req.method.as_str(), segments.as_slice()) {
+                ("PUT", ["api", "file", name]) => {
+                    created(percent_decode(name).unwrap(), req.body.clone())
+                }
+                ("POST", ["api", "file"]) => {
+                    let form = req.form();
+                    match (form.get("name"), form.get("file")) {
+                        (Some((_, name)), Some((_, data))) => {
+                            created(String::from_utf8_lossy(name).to_string(), data.clone())
+                        }
+                        _ => error(422, "no_file"),
+                    }
+                }
+                ("GET", ["api", "file", id, "info"]) => match store.files.get(*id) {
+                    Some(v) => MockResponse::new(200).body(info(id, v).to_string()),
+                    None => error(404, "not_found"),
+                },
+                ("GET", ["api", "file", id]) if query == "download" => {
+                    let stored = match store.files.get(*id) {
+                        Some(v) => v,
+                        None => return error(404, "not_found"),
+                    };
+                    match req
+                        .header("range")
+                        .and_then(|v| v.strip_prefix("bytes="))
+                        .and_then(|v| v.strip_suffix('-'))
+                    {
+                        Some(start) => {
+                            let start: usize = start.parse().unwrap();
+                            MockResponse::new(206).body(&stored.data[start..])
+                        }
+                        None => MockResponse::new(200).body(stored.data.clone()),
+                    }
+                }
+                ("DELETE", ["api", "file", id]) => match store.files.get(*id) {
+                    Some(v) if v.owner.is_some() && v.owner == owner => {
+                        store.files.remove(*id);
+                        let body = json!({"success": true, "value": "file_deleted"});
+                        MockResponse::new(200).body(body.to_string())
+                    }
+                    Some(_) => error(401, "unauthorized"),
+                    None => error(404, "not_found"),
+                },
+                ("GET", ["api", "user", "files"]) => {
+                    if owner.is_none() {
+                        return error(401, "authentication_required");
+                    }
+                    let files: Vec<Value> = store
+                        .files
+                        .iter()
+                        .filter(|(_, v)| v.owner == owner)
+                        .map(|(id, v)| info(id, v))
+                        .collect();
+                    MockResponse::new(200).body(json!({ "files": files }).to_string())
+                }
+                ("POST", ["api", "list"]) => {
+                    let body: Value = serde_json::from_slice(req.body.as_slice()).unwrap();
+                    let ids: Vec<String> = body["files"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|v| v["id"].as_str().unwrap().to_string())
+                        .collect();
+                    if ids.iter().any(|v| !store.files.contains_key(v)) {
+                        return error(422, "file_not_found");
+                    }
+                    let id = format!("L{}", store.lists.len());
+                    store.lists.insert(id.clone(), ids);
+                    let body = json!({"success": true, "id": id});
+                    MockResponse::new(201).body(body.to_string())
+                }
+                ("GET", ["api", "list", id]) => {
+                    let ids = match store.lists.get(*id) {
+                        Some(v) => v,
+                        None => return error(404, "not_found"),
+                    };
+                    let files: Vec<Value> = ids
+                        .iter()
+                        .filter_map(|v| store.files.get(v).map(|stored| info(v, stored)))
+                        .collect();
+                    let body = json!({"success": true, "id": id, "title": "t", "files": files});
+                    MockResponse::new(200).body(body.to_string())
+                }
+                _ => error(405, "method_not_allowed"),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn pixeldrain_roundtrip() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            name: &'a str,
+            len: Option<usize>,
+            anonymous: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "a b.txt",
+                len: Some(8),
+                anonymous: false,
+            },
+            TestCase {
+                name: "b.bin",
+                len: None,
+                anonymous: false,
+            },
+            TestCase {
+                name: "c.txt",
+                len: Some(8),
+                anonymous: true,
+            },
+        ];
+
+        let store = Arc::new(Mutex::new(Store::default()));
+        let base = format!("{}/api", server(store.clone()));
+        let auth_token = AuthToken::new("key").endpoint(base.as_str())?;
+        let anonymous = AuthToken::anonymous().endpoint(base.as_str())?;
+        let mut files = vec![];
+        for testcase in testcases {
+            let uploader = match testcase.anonymous {
+                true => &anonymous,
+                false => &auth_token,
+            };
+            let reader = Box::new(Cursor::new("abcdefgh"));
+            let file = Pixeldrain::upload(
+                testcase.name,
+                reader,
+                testcase.len,
+                UploadSetting {},
+                uploader,
+            )
+            .await?;
+            assert_eq!(
+                store.lock().unwrap().files.get(file.get_id()).unwrap().data,
+                b"abcdefgh"
+            );
+
+            let file = File::try_from(String::from(file))?;
+            let mut data = String::new();
+            Pixeldrain::download(file.clone(), DownloadSetting {}, &anonymous)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, "abcdefgh");
+
+            let mut data = String::new();
+            Pixeldrain::download_range(file.clone(), DownloadSetting {}, &anonymous, 5)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, "fgh");
+
+            let metadata = FileMetadata {
+                name: testcase.name.to_string(),
+                len: Some(8),
+            };
+            assert_eq!(Pixeldrain::metadata(&file, &anonymous).await?, metadata);
+            files.push((file, metadata));
+        }
+
+        let listed = Pixeldrain::list(&auth_token).await?;
+        assert_eq!(listed, files[..2].to_vec());
+        assert!(Pixeldrain::list(&anonymous).await.is_err());
+
+        let ids: Vec<File> = files.iter().map(|(v, _)| v.clone()).collect();
+        let list = Pixeldrain::create_list("t", ids.as_slice(), &anonymous).await?;
+        let list = FileList::try_from(String::from(list))?;
+        assert_eq!(Pixeldrain::list_files(&list, &anonymous).await?, files);
+
+        let (file, _) = files[2].clone();
+        let err = Pixeldrain::delete(file.clone(), &auth_token).await;
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "unexpected status 401 (unauthorized)."
+        );
+        let (file, _) = files[0].clone();
+        Pixeldrain::delete(file.clone(), &auth_token).await?;
+        assert_eq!(
+            Pixeldrain::probe(&file, &anonymous).await?,
+            LinkStatus::Removed
+        );
+        assert!(Pixeldrain::download(file, DownloadSetting {}, &anonymous)
+            .await
+            .is_err());
+        assert_eq!(
+            Pixeldrain::list_files(&list, &anonymous).await?,
+            files[1..].to_vec()
+        );
+
+        let wrong = AuthToken::new("wrong").endpoint(base.as_str())?;
+        let (file, _) = files[1].clone();
+        assert_eq!(Pixeldrain::probe(&file, &wrong).await?, LinkStatus::Private);
+
+        Ok(())
+    }
+}
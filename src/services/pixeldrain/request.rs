@@ -0,0 +1,73 @@
+use crate::FileMetadata;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use surf::Response;
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorResponse {
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdResponse {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileInfo {
+    pub id: String,
+    pub name: String,
+    pub size: usize,
+}
+
+impl From<&FileInfo> for FileMetadata {
+    fn from(info: &FileInfo) -> FileMetadata {
+        FileMetadata {
+            name: info.name.clone(),
+            len: Some(info.size),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FilesResponse {
+    pub files: Vec<FileInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListItem<'a> {
+    pub id: &'a str,
+    pub description: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateListRequest<'a> {
+    pub title: &'a str,
+    pub anonymous: bool,
+    pub files: Vec<ListItem<'a>>,
+}
+
+pub async fn check(res: &mut Response) -> anyhow::Result<()> {
+    if res.status().is_success() {
+        return Ok(());
+    }
+
+    let body = res.body_bytes().await.unwrap_or_default();
+    match serde_json::from_slice::<ErrorResponse>(body.as_slice()) {
+        Ok(v) => Err(anyhow::anyhow!(
+            "unexpected status {} ({}).",
+            res.status(),
+            v.value
+        )),
+        Err(_) => Err(anyhow::anyhow!("unexpected status {}.", res.status())),
+    }
+}
+
+pub async fn read_json<T: DeserializeOwned>(res: &mut Response) -> anyhow::Result<T> {
+    check(res).await?;
+    let body = match res.body_bytes().await {
+        Ok(v) => v,
+        Err(e) => return Err(e.into_inner()),
+    };
+    Ok(serde_json::from_slice(body.as_slice())?)
+}
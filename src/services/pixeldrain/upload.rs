@@ -0,0 +1,53 @@
+use super::{read_json, AuthToken, File, IdResponse};
+use crate::utils::{
+    gen_boundary, send, Multipart, MultipartContentEnum, MultipartField, BOUNDARY_CHARSET,
+};
+use futures::AsyncBufRead;
+use surf::http::Method;
+use surf::{Body, Client};
+
+pub async fn upload_file<'a>(
+    name: &'a str,
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    len: Option<usize>,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<File> {
+    let client = Client::new();
+
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(anyhow::anyhow!("unable to recognize the name {}.", name));
+    }
+
+    let mut res = match len {
+        Some(_) => {
+            let url = auth_token.api_url(&["file", name])?;
+            let body = Body::from_reader(reader, len);
+            send(&client, Method::Put, &url, vec![], Some(body), |req| {
+                auth_token.authorize(req)
+            })
+            .await?
+        }
+        None => {
+            let url = auth_token.api_url(&["file"])?;
+            let boundary = gen_boundary(16, BOUNDARY_CHARSET)?;
+            let content_type = format!("multipart/form-data; boundary={}", boundary);
+            let body = Multipart::new(MultipartField {
+                name: "file",
+                data: MultipartContentEnum::Reader(reader, name, len),
+            })
+            .chain(MultipartField {
+                name: "name",
+                data: MultipartContentEnum::Str(name),
+            })
+            .into_body(boundary.as_str());
+            let headers = vec![("Content-Type", content_type)];
+            send(&client, Method::Post, &url, headers, Some(body), |req| {
+                auth_token.authorize(req)
+            })
+            .await?
+        }
+    };
+    let uploaded: IdResponse = read_json(&mut res).await?;
+
+    Ok(File::new(uploaded.id.as_str()))
+}
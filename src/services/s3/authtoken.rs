@@ -0,0 +1,126 @@
+use anyhow::{anyhow, Result};
+use surf::Url;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthToken {
+    pub endpoint: Url,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub path_style: bool,
+}
+
+impl AuthToken {
+    pub fn new(
+        endpoint: &str,
+        region: &str,
+        bucket: &str,
+        access_key: &str,
+        secret_key: &str,
+    ) -> Result<AuthToken> {
+        let endpoint = Url::parse(endpoint)?;
+        if !matches!(endpoint.scheme(), "http" | "https") {
+            return Err(anyhow!(
+                "unable to recognize the scheme {}.",
+                endpoint.scheme()
+            ));
+        }
+        if endpoint.host_str().is_none() {
+            return Err(anyhow!("unable to recognize the endpoint {}.", endpoint));
+        }
+        if bucket.is_empty() {
+            return Err(anyhow!("bucket is empty."));
+        }
+
+        Ok(AuthToken {
+            endpoint,
+            region: region.to_string(),
+            bucket: bucket.to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+            path_style: true,
+        })
+    }
+
+    pub fn virtual_hosted(mut self) -> AuthToken {
+        self.path_style = false;
+        self
+    }
+
+    pub(super) fn object_url(&self, bucket: &str, key: &str) -> Result<Url> {
+        let mut url = self.endpoint.clone();
+        let key = key
+            .split('/')
+            .map(|v| uri_encode(v, true))
+            .collect::<Vec<String>>()
+            .join("/");
+        if self.path_style {
+            let base = url.path().trim_end_matches('/').to_string();
+            url.set_path(format!("{}/{}/{}", base, uri_encode(bucket, true), key).as_str());
+        } else {
+            let host = match url.host_str() {
+                Some(v) => format!("{}.{}", bucket, v),
+                None => return Err(anyhow!("unable to recognize the endpoint {}.", url)),
+            };
+            url.set_host(Some(host.as_str()))?;
+            url.set_path(format!("/{}", key).as_str());
+        }
+        Ok(url)
+    }
+}
+
+pub(super) fn uri_encode(src: &str, encode_slash: bool) -> String {
+    let mut encoded = String::new();
+    for byte in src.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(format!("%{:02X}", byte).as_str()),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AuthToken;
+
+    #[test]
+    fn object_url() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            endpoint: &'a str,
+            path_style: bool,
+            key: &'a str,
+            url: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                endpoint: "http://127.0.0.1:9000",
+                path_style: true,
+                key: "a/b c+(1).txt",
+                url: "http://127.0.0.1:9000/artifacts/a/b%20c%2B%281%29.txt",
+            },
+            TestCase {
+                endpoint: "https://s3.amazonaws.com",
+                path_style: false,
+                key: "test.txt",
+                url: "https://artifacts.s3.amazonaws.com/test.txt",
+            },
+        ];
+
+        for testcase in testcases {
+            let mut auth_token =
+                AuthToken::new(testcase.endpoint, "us-east-1", "artifacts", "a", "b")?;
+            if !testcase.path_style {
+                auth_token = auth_token.virtual_hosted();
+            }
+            let url = auth_token.object_url("artifacts", testcase.key)?;
+            assert_eq!(url.as_str(), testcase.url);
+        }
+        Ok(())
+    }
+}
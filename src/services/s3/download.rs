@@ -0,0 +1,61 @@
+use super::{check, send, AuthToken, File};
+use crate::utils::skip;
+use crate::FileMetadata;
+use futures::AsyncBufRead;
+use surf::http::{Method, StatusCode};
+use surf::Client;
+
+pub async fn download_file<'a>(
+    file: &'a File,
+    auth_token: &'a AuthToken,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let client = Client::new();
+    let url = auth_token.object_url(file.get_bucket(), file.get_key())?;
+
+    let mut headers = vec![];
+    if offset > 0 {
+        headers.push(("Range", format!("bytes={}-", offset)));
+    }
+    let mut res = send(&client, Method::Get, &url, headers, None, auth_token).await?;
+    check(&mut res).await?;
+
+    let mut reader = res.take_body().into_reader();
+    if offset > 0 && res.status() != StatusCode::PartialContent {
+        skip(&mut reader, offset).await?;
+    }
+
+    Ok(reader)
+}
+
+pub async fn probe_file<'a>(
+    file: &'a File,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<(StatusCode, Option<FileMetadata>)> {
+    let client = Client::new();
+    let url = auth_token.object_url(file.get_bucket(), file.get_key())?;
+
+    let headers = vec![("Range", String::from("bytes=0-0"))];
+    let res = send(&client, Method::Get, &url, headers, None, auth_token).await?;
+    let len = match res.status() {
+        StatusCode::PartialContent => res
+            .header("Content-Range")
+            .and_then(|v| v.last().as_str().rsplit_once('/'))
+            .and_then(|(_, v)| v.trim().parse().ok()),
+        StatusCode::RequestedRangeNotSatisfiable => Some(0),
+        v if v.is_success() => res
+            .header("Content-Length")
+            .and_then(|v| v.last().as_str().parse().ok()),
+        v => return Ok((v, None)),
+    };
+    let name = match file.get_key().rsplit_once('/') {
+        Some((_, name)) => name,
+        None => file.get_key(),
+    };
+
+    let metadata = FileMetadata {
+        name: name.to_string(),
+        len,
+    };
+    Ok((res.status(), Some(metadata)))
+}
@@ -0,0 +1,60 @@
+use anyhow::{anyhow, Error, Result};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct File {
+    bucket: String,
+    key: String,
+}
+
+impl File {
+    pub fn new(bucket: &str, key: &str) -> Result<File> {
+        if bucket.is_empty() || key.is_empty() {
+            return Err(anyhow!(
+                "unable to recognize the object {}/{}.",
+                bucket,
+                key
+            ));
+        }
+        Ok(File {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    pub fn get_bucket(&self) -> &str {
+        self.bucket.as_str()
+    }
+
+    pub fn get_key(&self) -> &str {
+        self.key.as_str()
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        let rest = match src.strip_prefix("s3://") {
+            Some(v) => v,
+            None => return Err(anyhow!("unable to recognize the uri {}.", src)),
+        };
+        match rest.split_once('/') {
+            Some((bucket, key)) => File::new(bucket, key),
+            None => Err(anyhow!("unable to recognize the uri {}.", src)),
+        }
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+impl From<File> for String {
+    fn from(file: File) -> String {
+        format!("s3://{}/{}", file.bucket, file.key)
+    }
+}
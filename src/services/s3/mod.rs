@@ -0,0 +1,420 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod sign;
+use sign::*;
+mod request;
+use request::*;
+mod download;
+use download::*;
+mod upload;
+use upload::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, Metadata, Probe, Service, Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use std::time::{Duration, SystemTime};
+use surf::http::{Method, StatusCode};
+use surf::{Client, Url};
+
+const MAX_PRESIGN_EXPIRES: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+pub struct S3 {}
+
+impl S3 {
+    pub fn presign(file: &File, auth_token: &AuthToken, expires: Duration) -> anyhow::Result<Url> {
+        if expires.is_zero() || expires > MAX_PRESIGN_EXPIRES {
+            return Err(anyhow::anyhow!(
+                "expiry must be between 1 second and {} seconds.",
+                MAX_PRESIGN_EXPIRES.as_secs()
+            ));
+        }
+        let url = auth_token.object_url(file.get_bucket(), file.get_key())?;
+        Ok(presign("GET", &url, expires, auth_token, SystemTime::now()))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UploadSetting {
+    pub prefix: String,
+    pub part_size: usize,
+}
+
+impl Default for UploadSetting {
+    fn default() -> UploadSetting {
+        UploadSetting {
+            prefix: String::new(),
+            part_size: 8 * 1024 * 1024,
+        }
+    }
+}
+
+impl Service for S3 {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for S3 {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, auth_token, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for S3 {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, auth_token, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for S3 {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        match probe_file(file, auth_token).await? {
+            (_, Some(metadata)) => Ok(metadata),
+            (status, None) => Err(anyhow::anyhow!("unexpected status {}.", status)),
+        }
+    }
+}
+
+#[async_trait]
+impl Probe for S3 {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        let status = match probe_file(file, auth_token).await? {
+            (_, Some(metadata)) => LinkStatus::Alive(metadata),
+            (StatusCode::NotFound, None) => LinkStatus::Removed,
+            (StatusCode::Unauthorized | StatusCode::Forbidden, None) => LinkStatus::Private,
+            (v, None) => LinkStatus::Unknown(format!("unexpected status {}.", v)),
+        };
+
+        Ok(status)
+    }
+}
+
+#[async_trait]
+impl Delete for S3 {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        let client = Client::new();
+        let url = auth_token.object_url(file.get_bucket(), file.get_key())?;
+        let mut res = send(&client, Method::Delete, &url, vec![], None, auth_token).await?;
+        check(&mut res).await
+    }
+}
+
+#[async_trait]
+impl Upload for S3 {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::MAX
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let key = format!("{}{}", setting.prefix, name);
+        let file = File::new(auth_token.bucket.as_str(), key.as_str())?;
+        upload_file(&file, reader, len, setting.part_size, auth_token).await?;
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sign::sha256_hex;
+    use super::upload::{MAX_PARTS, MIN_PART_SIZE};
+    use super::{AuthToken, DownloadSetting, File, UploadSetting, S3};
+    use crate::utils::{roundtrip, serve, MockRequest, MockResponse};
+    use crate::{FileMetadata, Upload};
+    use async_std::io::Cursor;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Store {
+        objects: BTreeMap<String, Vec<u8>>,
+        parts: BTreeMap<(String, usize), Vec<u8>>,
+        uploads: usize,
+        aborted: usize,
+    }
+
+    fn query<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+        query
+            .split('&')
+            .filter_map(|v| v.split_once('='))
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v)
+    }
+
+    fn handle(store: &mut Store, req: MockRequest) -> MockResponse {
+        let authorized = req
+            .header("authorization")
+            .map(|v| v.starts_with("AWS4-HMAC-SHA256 Credential=minio/"))
+            .unwrap_or(false);
+        if !authorized {
+            return MockResponse::new(403).body("<Error><Code>InvalidAccessKeyId</Code></Error>");
+        }
+        if req.header("x-amz-content-sha256") != Some(sha256_hex(req.body.as_slice()).as_str()) {
+            return MockResponse::new(400)
+                .body("<Error><Code>XAmzContentSHA256Mismatch</Code></Error>");
+        }
+
+        let (path, params) = req.path.split_once('?').unwrap_or((req.path.as_str(), ""));
+        let path = path.to_string();
+        match (req.method.as_str(), query(params, "uploadId")) {
+            ("POST", None) => {
+                store.uploads += 1;
+                MockResponse::new(200).body(format!(
+                    "<InitiateMultipartUploadResult><UploadId>u{}</UploadId></InitiateMultipartUploadResult>",
+                    store.uploads
+                ))
+            }
+            ("PUT", Some(id)) => {
+                let number: usize = query(params, "partNumber").unwrap().parse().unwrap();
+                if path.ends_with("fail.bin") && number == 2 {
+                    return MockResponse::new(500)
+                        .body("<Error><Code>InternalError</Code></Error>");
+                }
+                store
+                    .parts
+                    .insert((id.to_string(), number), req.body.clone());
+                MockResponse::new(200).header("ETag", format!("\"{}-{}\"", id, number).as_str())
+            }
+            ("POST", Some(id)) => {
+                let body = String::from_utf8_lossy(req.body.as_slice()).to_string();
+                let mut data = vec![];
+                for part in body.split("<PartNumber>").skip(1) {
+                    let number: usize = part.split('<').next().unwrap().parse().unwrap();
+                    data.extend(store.parts.remove(&(id.to_string(), number)).unwrap());
+                }
+                store.objects.insert(path, data);
+                MockResponse::new(200)
+                    .body("<CompleteMultipartUploadResult><ETag>\"x\"</ETag></CompleteMultipartUploadResult>")
+            }
+            ("DELETE", Some(_)) => {
+                store.aborted += 1;
+                MockResponse::new(204)
+            }
+            ("PUT", None) => {
+                store.objects.insert(path, req.body.clone());
+                MockResponse::new(200).header("ETag", "\"x\"")
+            }
+            ("DELETE", None) => {
+                store.objects.remove(&path);
+                MockResponse::new(204)
+            }
+            ("GET", None) => {
+                let data = match store.objects.get(&path) {
+                    Some(v) => v,
+                    None => {
+                        return MockResponse::new(404).body("<Error><Code>NoSuchKey</Code></Error>")
+                    }
+                };
+                MockResponse::ranged(&req, data)
+            }
+            _ => MockResponse::new(405),
+        }
+    }
+
+    #[tokio::test]
+    async fn s3_roundtrip() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            access_key: &'a str,
+            name: &'a str,
+            len: usize,
+            uploads: usize,
+            aborted: usize,
+            error: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                access_key: "minio",
+                name: "small.txt",
+                len: 3,
+                uploads: 0,
+                aborted: 0,
+                error: None,
+            },
+            TestCase {
+                access_key: "minio",
+                name: "large.txt",
+                len: MIN_PART_SIZE + 2,
+                uploads: 1,
+                aborted: 0,
+                error: None,
+            },
+            TestCase {
+                access_key: "minio",
+                name: "exact.txt",
+                len: MIN_PART_SIZE * 2,
+                uploads: 2,
+                aborted: 0,
+                error: None,
+            },
+            TestCase {
+                access_key: "minio",
+                name: "fail.bin",
+                len: MIN_PART_SIZE + 2,
+                uploads: 3,
+                aborted: 1,
+                error: Some("unexpected status 500 (InternalError)."),
+            },
+            TestCase {
+                access_key: "wrong",
+                name: "small.txt",
+                len: 3,
+                uploads: 3,
+                aborted: 1,
+                error: Some("unexpected status 403 (InvalidAccessKeyId)."),
+            },
+        ];
+
+        let store = Arc::new(Mutex::new(Store::default()));
+        let endpoint = {
+            let store = store.clone();
+            serve(move |req| handle(&mut store.lock().unwrap(), req))
+        };
+        for testcase in testcases {
+            let auth_token = AuthToken::new(
+                endpoint.as_str(),
+                "us-east-1",
+                "artifacts",
+                testcase.access_key,
+                "minio123",
+            )?;
+            let setting = UploadSetting {
+                prefix: String::from("builds/"),
+                part_size: MIN_PART_SIZE,
+            };
+            let content: Vec<u8> = (0..testcase.len).map(|i| b'a' + (i % 26) as u8).collect();
+            let reader = Box::new(Cursor::new(content.clone()));
+            let result = S3::upload(testcase.name, reader, None, setting, &auth_token).await;
+            {
+                let store = store.lock().unwrap();
+                assert_eq!(store.uploads, testcase.uploads);
+                assert_eq!(store.aborted, testcase.aborted);
+            }
+            let file = match testcase.error {
+                Some(v) => {
+                    assert_eq!(result.unwrap_err().to_string(), v);
+                    continue;
+                }
+                None => result?,
+            };
+            assert_eq!(
+                String::from(file.clone()),
+                format!("s3://artifacts/builds/{}", testcase.name)
+            );
+
+            let metadata = FileMetadata {
+                name: testcase.name.to_string(),
+                len: Some(testcase.len),
+            };
+            roundtrip::<S3>(file, DownloadSetting {}, &auth_token, &content, metadata).await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn s3_part_limits() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            len: Option<usize>,
+            part_size: usize,
+            error: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                len: None,
+                part_size: MIN_PART_SIZE - 1,
+                error: "part size must be at least 5242880 bytes.",
+            },
+            TestCase {
+                len: Some(MIN_PART_SIZE * MAX_PARTS + 1),
+                part_size: MIN_PART_SIZE,
+                error: "52428800001 bytes do not fit in 10000 parts of 5242880 bytes.",
+            },
+        ];
+
+        let auth_token = AuthToken::new("http://127.0.0.1:9", "us-east-1", "a", "minio", "b")?;
+        for testcase in testcases {
+            let setting = UploadSetting {
+                prefix: String::new(),
+                part_size: testcase.part_size,
+            };
+            let reader = Box::new(Cursor::new("abc"));
+            let result = S3::upload("a.txt", reader, testcase.len, setting, &auth_token).await;
+            assert_eq!(result.unwrap_err().to_string(), testcase.error);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn presign_expires() -> anyhow::Result<()> {
+        struct TestCase {
+            expires: Duration,
+            valid: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                expires: Duration::from_secs(3600),
+                valid: true,
+            },
+            TestCase {
+                expires: Duration::ZERO,
+                valid: false,
+            },
+            TestCase {
+                expires: Duration::from_secs(8 * 24 * 60 * 60),
+                valid: false,
+            },
+        ];
+
+        let auth_token = AuthToken::new("http://127.0.0.1:9000", "us-east-1", "a", "minio", "b")?;
+        let file = File::try_from("s3://artifacts/a b.txt")?;
+        for testcase in testcases {
+            let result = S3::presign(&file, &auth_token, testcase.expires);
+            assert_eq!(result.is_ok(), testcase.valid);
+            if let Ok(url) = result {
+                assert!(url
+                    .as_str()
+                    .starts_with("http://127.0.0.1:9000/artifacts/a%20b.txt?X-Amz-Algorithm="));
+                assert!(url.query().unwrap().contains("&X-Amz-Signature="));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use super::{sha256_hex, sign, AuthToken};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::time::SystemTime;
+use surf::http::Method;
+use surf::{Body, Client, Response, Url};
+
+pub async fn send<'a>(
+    client: &'a Client,
+    method: Method,
+    url: &'a Url,
+    headers: Vec<(&'a str, String)>,
+    body: Option<Vec<u8>>,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<Response> {
+    let payload_hash = sha256_hex(body.as_deref().unwrap_or_default());
+    let signed = sign(
+        method.to_string().as_str(),
+        url,
+        headers.as_slice(),
+        payload_hash.as_str(),
+        auth_token,
+        SystemTime::now(),
+    );
+
+    let body = body.map(Body::from_bytes);
+    crate::utils::send(client, method, url, vec![], body, |mut req| {
+        for (k, v) in signed.iter() {
+            req = req.header(k.as_str(), v.as_str());
+        }
+        req
+    })
+    .await
+}
+
+pub async fn check(res: &mut Response) -> anyhow::Result<()> {
+    if res.status().is_success() {
+        return Ok(());
+    }
+
+    let body = res.body_string().await.unwrap_or_default();
+    match find_text(body.as_str(), "Code") {
+        Some(code) => Err(anyhow::anyhow!(
+            "unexpected status {} ({}).",
+            res.status(),
+            code
+        )),
+        None => Err(anyhow::anyhow!("unexpected status {}.", res.status())),
+    }
+}
+
+pub fn find_text(xml: &str, name: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut inside = false;
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) => inside = e.local_name().as_ref() == name.as_bytes(),
+            Event::Text(e) if inside => return Some(e.unescape().ok()?.to_string()),
+            Event::End(_) => inside = false,
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use super::{check, find_text, send, AuthToken, File};
+use futures::{AsyncBufRead, AsyncBufReadExt};
+use surf::http::Method;
+use surf::{Client, Url};
+
+pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+pub const MAX_PARTS: usize = 10_000;
+
+async fn read_chunk(
+    reader: &mut Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    size: usize,
+) -> anyhow::Result<Vec<u8>> {
+    let mut chunk = vec![];
+    while chunk.len() < size {
+        let data = reader.fill_buf().await?;
+        if data.is_empty() {
+            break;
+        }
+        let n = data.len().min(size - chunk.len());
+        chunk.extend_from_slice(&data[..n]);
+        reader.consume_unpin(n);
+    }
+    Ok(chunk)
+}
+
+fn with_query(url: &Url, query: &str) -> Url {
+    let mut url = url.clone();
+    url.set_query(Some(query));
+    url
+}
+
+pub async fn upload_file<'a>(
+    file: &'a File,
+    mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    len: Option<usize>,
+    part_size: usize,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<()> {
+    let client = Client::new();
+    let url = auth_token.object_url(file.get_bucket(), file.get_key())?;
+    if part_size < MIN_PART_SIZE {
+        return Err(anyhow::anyhow!(
+            "part size must be at least {} bytes.",
+            MIN_PART_SIZE
+        ));
+    }
+    if let Some(len) = len {
+        if len > part_size.saturating_mul(MAX_PARTS) {
+            return Err(anyhow::anyhow!(
+                "{} bytes do not fit in {} parts of {} bytes.",
+                len,
+                MAX_PARTS,
+                part_size
+            ));
+        }
+    }
+
+    let first = read_chunk(&mut reader, part_size).await?;
+    if first.len() < part_size {
+        let mut res = send(&client, Method::Put, &url, vec![], Some(first), auth_token).await?;
+        return check(&mut res).await;
+    }
+
+    let mut res = send(
+        &client,
+        Method::Post,
+        &with_query(&url, "uploads="),
+        vec![],
+        None,
+        auth_token,
+    )
+    .await?;
+    check(&mut res).await?;
+    let body = match res.body_string().await {
+        Ok(v) => v,
+        Err(e) => return Err(e.into_inner()),
+    };
+    let upload_id = match find_text(body.as_str(), "UploadId") {
+        Some(v) => v,
+        None => return Err(anyhow::anyhow!("unable to find the upload id.")),
+    };
+
+    let result = upload_parts(
+        &client,
+        &url,
+        upload_id.as_str(),
+        first,
+        reader,
+        part_size,
+        auth_token,
+    )
+    .await;
+    if result.is_err() {
+        let query = format!("uploadId={}", super::uri_encode(upload_id.as_str(), true));
+        let _ = send(
+            &client,
+            Method::Delete,
+            &with_query(&url, query.as_str()),
+            vec![],
+            None,
+            auth_token,
+        )
+        .await;
+    }
+    result
+}
+
+async fn upload_parts<'a>(
+    client: &'a Client,
+    url: &'a Url,
+    upload_id: &'a str,
+    first: Vec<u8>,
+    mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    part_size: usize,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<()> {
+    let upload_id = super::uri_encode(upload_id, true);
+
+    let mut etags = vec![];
+    let mut chunk = first;
+    while !chunk.is_empty() {
+        let number = etags.len() + 1;
+        if number > MAX_PARTS {
+            return Err(anyhow::anyhow!(
+                "too many parts, the limit is {}.",
+                MAX_PARTS
+            ));
+        }
+        let query = format!("partNumber={}&uploadId={}", number, upload_id);
+        let mut res = send(
+            client,
+            Method::Put,
+            &with_query(url, query.as_str()),
+            vec![],
+            Some(chunk),
+            auth_token,
+        )
+        .await?;
+        check(&mut res).await?;
+        match res.header("ETag") {
+            Some(v) => etags.push(v.last().to_string()),
+            None => {
+                return Err(anyhow::anyhow!(
+                    "unable to find the etag of part {}.",
+                    number
+                ))
+            }
+        }
+
+        chunk = read_chunk(&mut reader, part_size).await?;
+    }
+
+    let parts: String = etags
+        .iter()
+        .enumerate()
+        .map(|(i, etag)| {
+            format!(
+                "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                i + 1,
+                quick_xml::escape::escape(etag.as_str())
+            )
+        })
+        .collect();
+    let body = format!(
+        "<CompleteMultipartUpload xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">{}</CompleteMultipartUpload>",
+        parts
+    );
+    let query = format!("uploadId={}", upload_id);
+    let mut res = send(
+        client,
+        Method::Post,
+        &with_query(url, query.as_str()),
+        vec![("Content-Type", String::from("application/xml"))],
+        Some(body.into_bytes()),
+        auth_token,
+    )
+    .await?;
+    check(&mut res).await?;
+
+    let body = match res.body_string().await {
+        Ok(v) => v,
+        Err(e) => return Err(e.into_inner()),
+    };
+    if let Some(code) = find_text(body.as_str(), "Code") {
+        return Err(anyhow::anyhow!("unable to complete the upload ({}).", code));
+    }
+
+    Ok(())
+}
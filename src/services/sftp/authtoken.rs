@@ -0,0 +1,199 @@
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use ssh2::{CheckResult, HashType, KnownHostFileKind, Session};
+use std::net::TcpStream;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Credential {
+    Password {
+        username: String,
+        password: String,
+    },
+    Key {
+        username: String,
+        private_key: PathBuf,
+        passphrase: Option<String>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthToken {
+    pub host: String,
+    pub port: u16,
+    pub credential: Credential,
+    pub root: String,
+    pub fingerprint: Option<String>,
+    pub known_hosts: Option<PathBuf>,
+    pub accept_any_host_key: bool,
+}
+
+impl AuthToken {
+    pub fn new(host: &str, port: u16, credential: Credential, root: &str) -> AuthToken {
+        let root = match root.trim_end_matches('/') {
+            "" if root.starts_with('/') => "/",
+            v => v,
+        };
+        AuthToken {
+            host: host.to_string(),
+            port,
+            credential,
+            root: root.to_string(),
+            fingerprint: None,
+            known_hosts: None,
+            accept_any_host_key: false,
+        }
+    }
+
+    pub fn fingerprint(mut self, fingerprint: &str) -> AuthToken {
+        self.fingerprint = Some(fingerprint.to_string());
+        self
+    }
+
+    pub fn known_hosts(mut self, path: PathBuf) -> AuthToken {
+        self.known_hosts = Some(path);
+        self
+    }
+
+    pub fn danger_accept_any_host_key(mut self) -> AuthToken {
+        self.accept_any_host_key = true;
+        self
+    }
+
+    pub(super) fn remote_path(&self, path: &str) -> String {
+        match self.root.as_str() {
+            "" => path.to_string(),
+            "/" => format!("/{}", path),
+            root => format!("{}/{}", root, path),
+        }
+    }
+
+    pub(super) fn connect(&self) -> Result<Session> {
+        let stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        let mut session = Session::new()?;
+        session.set_tcp_stream(stream);
+        session.handshake()?;
+
+        if let Some(expected) = self.fingerprint.as_ref() {
+            let actual = match session.host_key_hash(HashType::Sha256) {
+                Some(v) => format!("SHA256:{}", STANDARD_NO_PAD.encode(v)),
+                None => return Err(anyhow!("unable to read the host key.")),
+            };
+            if &actual != expected {
+                return Err(anyhow!("host key {} does not match.", actual));
+            }
+        } else if !self.accept_any_host_key {
+            self.check_known_hosts(&session)?;
+        }
+
+        match &self.credential {
+            Credential::Password { username, password } => {
+                session.userauth_password(username, password)?;
+            }
+            Credential::Key {
+                username,
+                private_key,
+                passphrase,
+            } => {
+                session.userauth_pubkey_file(
+                    username,
+                    None,
+                    private_key.as_path(),
+                    passphrase.as_deref(),
+                )?;
+            }
+        }
+        if !session.authenticated() {
+            return Err(anyhow!("authentication failed."));
+        }
+
+        Ok(session)
+    }
+
+    fn check_known_hosts(&self, session: &Session) -> Result<()> {
+        let path = match (&self.known_hosts, std::env::var_os("HOME")) {
+            (Some(v), _) => v.clone(),
+            (None, Some(home)) => PathBuf::from(home).join(".ssh/known_hosts"),
+            (None, None) => return Err(anyhow!("unable to locate known_hosts.")),
+        };
+        let mut known_hosts = session.known_hosts()?;
+        known_hosts.read_file(path.as_path(), KnownHostFileKind::OpenSSH)?;
+        let key = match session.host_key() {
+            Some((v, _)) => v,
+            None => return Err(anyhow!("unable to read the host key.")),
+        };
+        match known_hosts.check_port(self.host.as_str(), self.port, key) {
+            CheckResult::Match => Ok(()),
+            CheckResult::NotFound => Err(anyhow!("host {} is not in known_hosts.", self.host)),
+            CheckResult::Mismatch => Err(anyhow!(
+                "host key of {} does not match known_hosts.",
+                self.host
+            )),
+            CheckResult::Failure => Err(anyhow!("unable to check the host key.")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, Credential};
+
+    #[test]
+    fn remote_path() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            root: &'a str,
+            path: &'a str,
+            remote: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                root: "/upload/",
+                path: "a/b.txt",
+                remote: "/upload/a/b.txt",
+            },
+            TestCase {
+                root: "/",
+                path: "b.txt",
+                remote: "/b.txt",
+            },
+            TestCase {
+                root: "",
+                path: "b.txt",
+                remote: "b.txt",
+            },
+            TestCase {
+                root: "incoming",
+                path: "b.txt",
+                remote: "incoming/b.txt",
+            },
+        ];
+
+        for testcase in testcases {
+            let credential = Credential::Password {
+                username: String::from("user"),
+                password: String::from("pass"),
+            };
+            let auth_token = AuthToken::new("127.0.0.1", 22, credential, testcase.root);
+            assert_eq!(auth_token.remote_path(testcase.path), testcase.remote);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn connect_refused() -> anyhow::Result<()> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        drop(listener);
+
+        let credential = Credential::Key {
+            username: String::from("user"),
+            private_key: std::path::PathBuf::from("id_ed25519"),
+            passphrase: None,
+        };
+        let auth_token = AuthToken::new("127.0.0.1", port, credential, "/");
+        assert!(auth_token.connect().is_err());
+        Ok(())
+    }
+}
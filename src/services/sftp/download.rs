@@ -0,0 +1,101 @@
+use super::{with_remote, AuthToken, Connector, File};
+use crate::utils::pipe;
+use crate::{FileMetadata, LinkStatus};
+use futures::executor::block_on;
+use futures::{AsyncBufRead, AsyncWriteExt};
+use ssh2::ErrorCode;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const LIBSSH2_FX_NO_SUCH_FILE: i32 = 2;
+const LIBSSH2_FX_PERMISSION_DENIED: i32 = 3;
+const LIBSSH2_FX_NO_SUCH_PATH: i32 = 10;
+
+pub async fn download_file<'a, C: Connector>(
+    connector: &'a C,
+    auth_token: &'a AuthToken,
+    file: &'a File,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let path = auth_token.remote_path(file.get_path());
+    let mut remote = with_remote(connector, move |sftp| {
+        let path = Path::new(path.as_str());
+        let len = sftp.stat(path)?.size.unwrap_or(0) as usize;
+        if offset > len {
+            return Err(anyhow::anyhow!("offset is larger than {}.", len));
+        }
+        let mut remote = sftp.open(path)?;
+        remote.seek(SeekFrom::Start(offset as u64))?;
+        Ok(remote)
+    })
+    .await?;
+
+    let (mut writer, reader) = pipe(16);
+    async_std::task::spawn_blocking(move || {
+        let mut buf = vec![0u8; 32 * 1024];
+        loop {
+            match remote.read(buf.as_mut_slice()) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if block_on(writer.write_all(&buf[..n])).is_err() {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    block_on(writer.fail(e));
+                    return;
+                }
+            }
+        }
+        let _ = block_on(writer.close());
+    });
+
+    Ok(Box::new(reader))
+}
+
+pub async fn file_metadata<'a, C: Connector>(
+    connector: &'a C,
+    auth_token: &'a AuthToken,
+    file: &'a File,
+) -> anyhow::Result<FileMetadata> {
+    let path = auth_token.remote_path(file.get_path());
+    let name = file.get_name().to_string();
+    with_remote(connector, move |sftp| {
+        let stat = sftp.stat(Path::new(path.as_str()))?;
+        if !stat.is_file() {
+            return Err(anyhow::anyhow!("{} is not a file.", path));
+        }
+        Ok(FileMetadata {
+            name,
+            len: stat.size.map(|v| v as usize),
+        })
+    })
+    .await
+}
+
+pub async fn file_status<'a, C: Connector>(
+    connector: &'a C,
+    auth_token: &'a AuthToken,
+    file: &'a File,
+) -> anyhow::Result<LinkStatus> {
+    let path = auth_token.remote_path(file.get_path());
+    let name = file.get_name().to_string();
+    with_remote(connector, move |sftp| {
+        let status = match sftp.stat(Path::new(path.as_str())) {
+            Ok(stat) if stat.is_file() => LinkStatus::Alive(FileMetadata {
+                name,
+                len: stat.size.map(|v| v as usize),
+            }),
+            Ok(_) => LinkStatus::Removed,
+            Err(e) => match e.code() {
+                ErrorCode::SFTP(LIBSSH2_FX_NO_SUCH_FILE | LIBSSH2_FX_NO_SUCH_PATH) => {
+                    LinkStatus::Removed
+                }
+                ErrorCode::SFTP(LIBSSH2_FX_PERMISSION_DENIED) => LinkStatus::Private,
+                _ => LinkStatus::Unknown(e.to_string()),
+            },
+        };
+        Ok(status)
+    })
+    .await
+}
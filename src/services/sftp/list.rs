@@ -0,0 +1,55 @@
+use super::{with_remote, AuthToken, Connector};
+use crate::FileMetadata;
+use std::path::Path;
+
+pub async fn list_files<C: Connector>(
+    connector: &C,
+    auth_token: &AuthToken,
+) -> anyhow::Result<Vec<(String, FileMetadata)>> {
+    let root = auth_token.clone();
+    with_remote(connector, move |sftp| {
+        let mut files = vec![];
+        let mut dirs = vec![String::new()];
+        while let Some(dir) = dirs.pop() {
+            let remote = match dir.is_empty() {
+                true if root.root.is_empty() => String::from("."),
+                true => root.root.clone(),
+                false => root.remote_path(dir.as_str()),
+            };
+            for (path, stat) in sftp.readdir(Path::new(remote.as_str()))? {
+                let name = match path.file_name() {
+                    Some(v) => v.to_string_lossy().to_string(),
+                    None => continue,
+                };
+                if name == "." || name == ".." {
+                    continue;
+                }
+                let path = match dir.is_empty() {
+                    true => name.clone(),
+                    false => format!("{}/{}", dir, name),
+                };
+                if stat.is_dir() {
+                    dirs.push(path);
+                } else if stat.is_file() {
+                    let metadata = FileMetadata {
+                        name,
+                        len: stat.size.map(|v| v as usize),
+                    };
+                    files.push((path, metadata));
+                }
+            }
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(files)
+    })
+    .await
+}
+
+pub async fn delete_file<C: Connector>(connector: &C, path: String) -> anyhow::Result<()> {
+    with_remote(connector, move |sftp| {
+        sftp.unlink(Path::new(path.as_str()))?;
+        Ok(())
+    })
+    .await
+}
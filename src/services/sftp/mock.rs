@@ -0,0 +1,212 @@
+use super::{Connector, Remote, RemoteFile};
+use ssh2::{ErrorCode, FileStat};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const LIBSSH2_FX_NO_SUCH_FILE: i32 = 2;
+const LIBSSH2_FX_PERMISSION_DENIED: i32 = 3;
+
+#[derive(Debug, Default)]
+pub struct MockStore {
+    pub files: BTreeMap<String, Vec<u8>>,
+    pub dirs: BTreeSet<String>,
+    pub denied: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MockRemote {
+    pub store: Arc<Mutex<MockStore>>,
+}
+
+fn stat(size: Option<u64>, perm: u32) -> FileStat {
+    FileStat {
+        size,
+        uid: None,
+        gid: None,
+        perm: Some(perm),
+        atime: None,
+        mtime: None,
+    }
+}
+
+fn no_such_file() -> ssh2::Error {
+    ssh2::Error::new(ErrorCode::SFTP(LIBSSH2_FX_NO_SUCH_FILE), "no such file")
+}
+
+fn parent(path: &Path) -> String {
+    path.parent()
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+impl MockStore {
+    fn check(&self, path: &str) -> Result<(), ssh2::Error> {
+        match self.denied.contains(path) {
+            true => Err(ssh2::Error::new(
+                ErrorCode::SFTP(LIBSSH2_FX_PERMISSION_DENIED),
+                "permission denied",
+            )),
+            false => Ok(()),
+        }
+    }
+
+    fn has_dir(&self, path: &str) -> bool {
+        path.is_empty() || path == "/" || self.dirs.contains(path)
+    }
+}
+
+impl Connector for MockRemote {
+    fn remote(&self) -> anyhow::Result<Box<dyn Remote>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+impl Remote for MockRemote {
+    fn stat(&self, path: &Path) -> Result<FileStat, ssh2::Error> {
+        let store = self.store.lock().unwrap();
+        let path = path.to_string_lossy();
+        store.check(&path)?;
+        if let Some(data) = store.files.get(path.as_ref()) {
+            return Ok(stat(Some(data.len() as u64), 0o100644));
+        }
+        match store.dirs.contains(path.as_ref()) {
+            true => Ok(stat(None, 0o040755)),
+            false => Err(no_such_file()),
+        }
+    }
+
+    fn mkdir(&self, path: &Path) -> Result<(), ssh2::Error> {
+        let mut store = self.store.lock().unwrap();
+        if !store.has_dir(parent(path).as_str()) {
+            return Err(no_such_file());
+        }
+        store.dirs.insert(path.to_string_lossy().to_string());
+        Ok(())
+    }
+
+    fn readdir(&self, path: &Path) -> Result<Vec<(PathBuf, FileStat)>, ssh2::Error> {
+        let store = self.store.lock().unwrap();
+        let dir = path.to_string_lossy().to_string();
+        if !store.dirs.contains(&dir) {
+            return Err(no_such_file());
+        }
+        let mut entries = vec![];
+        for (file, data) in store.files.iter() {
+            if parent(Path::new(file)) == dir {
+                entries.push((PathBuf::from(file), stat(Some(data.len() as u64), 0o100644)));
+            }
+        }
+        for sub in store.dirs.iter() {
+            if parent(Path::new(sub)) == dir {
+                entries.push((PathBuf::from(sub), stat(None, 0o040755)));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn unlink(&self, path: &Path) -> Result<(), ssh2::Error> {
+        let mut store = self.store.lock().unwrap();
+        match store.files.remove(path.to_string_lossy().as_ref()) {
+            Some(_) => Ok(()),
+            None => Err(no_such_file()),
+        }
+    }
+
+    fn open(&self, path: &Path) -> Result<Box<dyn RemoteFile>, ssh2::Error> {
+        let store = self.store.lock().unwrap();
+        let path = path.to_string_lossy().to_string();
+        store.check(&path)?;
+        if !store.files.contains_key(&path) {
+            return Err(no_such_file());
+        }
+        Ok(Box::new(MockFile {
+            store: self.store.clone(),
+            path,
+            pos: 0,
+        }))
+    }
+
+    fn create(&self, path: &Path, truncate: bool) -> Result<Box<dyn RemoteFile>, ssh2::Error> {
+        let mut store = self.store.lock().unwrap();
+        if !store.has_dir(parent(path).as_str()) {
+            return Err(no_such_file());
+        }
+        let path = path.to_string_lossy().to_string();
+        store.check(&path)?;
+        let data = store.files.entry(path.clone()).or_default();
+        if truncate {
+            data.clear();
+        }
+        Ok(Box::new(MockFile {
+            store: self.store.clone(),
+            path,
+            pos: 0,
+        }))
+    }
+}
+
+struct MockFile {
+    store: Arc<Mutex<MockStore>>,
+    path: String,
+    pos: usize,
+}
+
+impl MockFile {
+    fn with_data<T>(&self, f: impl FnOnce(&mut Vec<u8>) -> T) -> io::Result<T> {
+        let mut store = self.store.lock().unwrap();
+        match store.files.get_mut(&self.path) {
+            Some(data) => Ok(f(data)),
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+}
+
+impl Read for MockFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let pos = self.pos;
+        let n = self.with_data(|data| {
+            let rest = &data[pos.min(data.len())..];
+            let n = rest.len().min(buf.len());
+            buf[..n].copy_from_slice(&rest[..n]);
+            n
+        })?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl Write for MockFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let pos = self.pos;
+        self.with_data(|data| {
+            if data.len() < pos + buf.len() {
+                data.resize(pos + buf.len(), 0);
+            }
+            data[pos..pos + buf.len()].copy_from_slice(buf);
+        })?;
+        self.pos += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for MockFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let len = self.with_data(|data| data.len())? as i64;
+        let pos = match pos {
+            SeekFrom::Start(v) => v as i64,
+            SeekFrom::End(v) => len + v,
+            SeekFrom::Current(v) => self.pos as i64 + v,
+        };
+        if pos < 0 {
+            return Err(io::Error::from(io::ErrorKind::InvalidInput));
+        }
+        self.pos = pos as usize;
+        Ok(self.pos as u64)
+    }
+}
@@ -0,0 +1,295 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod download;
+use download::*;
+mod list;
+use list::*;
+mod upload;
+use upload::*;
+mod remote;
+use remote::*;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+use mock::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Service,
+    Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+
+pub struct Sftp {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UploadSetting {
+    pub prefix: String,
+    pub resume: bool,
+}
+
+impl Service for Sftp {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for Sftp {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(auth_token, auth_token, &file, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Sftp {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(auth_token, auth_token, &file, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for Sftp {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        file_metadata(auth_token, auth_token, file).await
+    }
+}
+
+#[async_trait]
+impl Probe for Sftp {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        file_status(auth_token, auth_token, file).await
+    }
+}
+
+#[async_trait]
+impl Delete for Sftp {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        delete_file(auth_token, auth_token.remote_path(file.get_path())).await
+    }
+}
+
+#[async_trait]
+impl List for Sftp {
+    async fn list<'a>(
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Vec<(Self::File, FileMetadata)>> {
+        let mut files = vec![];
+        for (path, metadata) in list_files(auth_token, auth_token).await? {
+            files.push((File::new(path.as_str())?, metadata));
+        }
+        Ok(files)
+    }
+}
+
+#[async_trait]
+impl Upload for Sftp {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::MAX
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let file = File::new(format!("{}{}", setting.prefix, name).as_str())?;
+        upload_file(auth_token, auth_token, &file, reader, len, setting.resume).await?;
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        delete_file, download_file, file_metadata, file_status, list_files, upload_file, AuthToken,
+        Credential, File, MockRemote, UploadSetting,
+    };
+    use crate::{FileMetadata, LinkStatus};
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+
+    #[tokio::test]
+    async fn sftp_roundtrip() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            name: &'a str,
+            setting: UploadSetting,
+            existing: Option<&'a str>,
+            data: &'a str,
+            stored: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "a.txt",
+                setting: UploadSetting::default(),
+                existing: None,
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+            TestCase {
+                name: "b.bin",
+                setting: UploadSetting {
+                    resume: true,
+                    ..UploadSetting::default()
+                },
+                existing: Some("abc"),
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+            TestCase {
+                name: "c.bin",
+                setting: UploadSetting::default(),
+                existing: Some("stale data"),
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+            TestCase {
+                name: "d.txt",
+                setting: UploadSetting {
+                    prefix: String::from("in/deep/"),
+                    resume: true,
+                },
+                existing: None,
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+            TestCase {
+                name: "e.bin",
+                setting: UploadSetting {
+                    resume: true,
+                    ..UploadSetting::default()
+                },
+                existing: Some("stale data!"),
+                data: "abcdefgh",
+                stored: "abcdefgh",
+            },
+        ];
+
+        let mock = MockRemote::default();
+        mock.store
+            .lock()
+            .unwrap()
+            .dirs
+            .insert(String::from("/drop"));
+        let credential = Credential::Password {
+            username: String::from("user"),
+            password: String::from("pass"),
+        };
+        let auth_token = AuthToken::new("127.0.0.1", 22, credential, "/drop/");
+        for testcase in testcases {
+            let path = format!("/drop/{}{}", testcase.setting.prefix, testcase.name);
+            if let Some(v) = testcase.existing {
+                mock.store
+                    .lock()
+                    .unwrap()
+                    .files
+                    .insert(path.clone(), v.as_bytes().to_vec());
+            }
+
+            let file = File::new(format!("{}{}", testcase.setting.prefix, testcase.name).as_str())?;
+            let reader = Box::new(Cursor::new(testcase.data));
+            let len = Some(testcase.data.len());
+            upload_file(
+                &mock,
+                &auth_token,
+                &file,
+                reader,
+                len,
+                testcase.setting.resume,
+            )
+            .await?;
+            assert_eq!(
+                mock.store
+                    .lock()
+                    .unwrap()
+                    .files
+                    .get(&path)
+                    .map(|v| v.as_slice()),
+                Some(testcase.stored.as_bytes())
+            );
+
+            let mut data = String::new();
+            download_file(&mock, &auth_token, &file, 0)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, testcase.stored);
+
+            let mut data = String::new();
+            download_file(&mock, &auth_token, &file, 3)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, &testcase.stored[3..]);
+
+            let metadata = FileMetadata {
+                name: testcase.name.to_string(),
+                len: Some(testcase.stored.len()),
+            };
+            assert_eq!(file_metadata(&mock, &auth_token, &file).await?, metadata);
+            assert_eq!(
+                file_status(&mock, &auth_token, &file).await?,
+                LinkStatus::Alive(metadata)
+            );
+        }
+
+        let listed: Vec<String> = list_files(&mock, &auth_token)
+            .await?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(
+            listed,
+            vec!["a.txt", "b.bin", "c.bin", "e.bin", "in/deep/d.txt"]
+        );
+
+        let file = File::try_from("sftp://a.txt")?;
+        delete_file(&mock, auth_token.remote_path(file.get_path())).await?;
+        assert_eq!(
+            file_status(&mock, &auth_token, &file).await?,
+            LinkStatus::Removed
+        );
+        assert!(download_file(&mock, &auth_token, &file, 0).await.is_err());
+        let file = File::try_from("sftp://b.bin")?;
+        assert!(download_file(&mock, &auth_token, &file, 9).await.is_err());
+
+        mock.store
+            .lock()
+            .unwrap()
+            .denied
+            .insert(String::from("/drop/c.bin"));
+        let file = File::try_from("sftp://c.bin")?;
+        assert_eq!(
+            file_status(&mock, &auth_token, &file).await?,
+            LinkStatus::Private
+        );
+
+        Ok(())
+    }
+}
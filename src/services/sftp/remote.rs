@@ -0,0 +1,73 @@
+use super::AuthToken;
+use ssh2::{FileStat, OpenFlags, OpenType, Sftp};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+pub trait RemoteFile: Read + Write + Seek + Send {}
+
+impl<T: Read + Write + Seek + Send> RemoteFile for T {}
+
+pub trait Remote {
+    fn stat(&self, path: &Path) -> Result<FileStat, ssh2::Error>;
+    fn mkdir(&self, path: &Path) -> Result<(), ssh2::Error>;
+    fn readdir(&self, path: &Path) -> Result<Vec<(PathBuf, FileStat)>, ssh2::Error>;
+    fn unlink(&self, path: &Path) -> Result<(), ssh2::Error>;
+    fn open(&self, path: &Path) -> Result<Box<dyn RemoteFile>, ssh2::Error>;
+    fn create(&self, path: &Path, truncate: bool) -> Result<Box<dyn RemoteFile>, ssh2::Error>;
+}
+
+pub trait Connector: Clone + Send + 'static {
+    fn remote(&self) -> anyhow::Result<Box<dyn Remote>>;
+}
+
+impl Connector for AuthToken {
+    fn remote(&self) -> anyhow::Result<Box<dyn Remote>> {
+        let session = self.connect()?;
+        Ok(Box::new(session.sftp()?))
+    }
+}
+
+pub async fn with_remote<C, T, F>(connector: &C, f: F) -> anyhow::Result<T>
+where
+    C: Connector,
+    F: FnOnce(&dyn Remote) -> anyhow::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let connector = connector.clone();
+    async_std::task::spawn_blocking(move || f(connector.remote()?.as_ref())).await
+}
+
+impl Remote for Sftp {
+    fn stat(&self, path: &Path) -> Result<FileStat, ssh2::Error> {
+        Sftp::stat(self, path)
+    }
+
+    fn mkdir(&self, path: &Path) -> Result<(), ssh2::Error> {
+        Sftp::mkdir(self, path, 0o755)
+    }
+
+    fn readdir(&self, path: &Path) -> Result<Vec<(PathBuf, FileStat)>, ssh2::Error> {
+        Sftp::readdir(self, path)
+    }
+
+    fn unlink(&self, path: &Path) -> Result<(), ssh2::Error> {
+        Sftp::unlink(self, path)
+    }
+
+    fn open(&self, path: &Path) -> Result<Box<dyn RemoteFile>, ssh2::Error> {
+        Ok(Box::new(Sftp::open(self, path)?))
+    }
+
+    fn create(&self, path: &Path, truncate: bool) -> Result<Box<dyn RemoteFile>, ssh2::Error> {
+        let flags = match truncate {
+            true => OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+            false => OpenFlags::WRITE,
+        };
+        Ok(Box::new(self.open_mode(
+            path,
+            flags,
+            0o644,
+            OpenType::File,
+        )?))
+    }
+}
@@ -0,0 +1,61 @@
+use super::{with_remote, AuthToken, Connector, File};
+use crate::utils::skip;
+use futures::executor::block_on;
+use futures::{AsyncBufRead, AsyncBufReadExt};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+pub async fn upload_file<'a, C: Connector>(
+    connector: &'a C,
+    auth_token: &'a AuthToken,
+    file: &'a File,
+    mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    len: Option<usize>,
+    resume: bool,
+) -> anyhow::Result<()> {
+    let path = auth_token.remote_path(file.get_path());
+    let parents: Vec<String> = {
+        let segments: Vec<&str> = file.get_path().split('/').collect();
+        (1..segments.len())
+            .map(|i| auth_token.remote_path(segments[..i].join("/").as_str()))
+            .collect()
+    };
+
+    with_remote(connector, move |sftp| {
+        for parent in parents {
+            let parent = Path::new(parent.as_str());
+            if sftp.stat(parent).is_err() {
+                sftp.mkdir(parent)?;
+            }
+        }
+
+        let path = Path::new(path.as_str());
+        // Resuming trusts that the remote prefix matches the source; only a
+        // remote file longer than the source is known to be stale.
+        let offset = match resume {
+            true => sftp.stat(path).ok().and_then(|v| v.size).unwrap_or(0),
+            false => 0,
+        };
+        let offset = match len {
+            Some(len) if offset > len as u64 => 0,
+            _ => offset,
+        };
+        let mut remote = sftp.create(path, offset == 0)?;
+        remote.seek(SeekFrom::Start(offset))?;
+        block_on(skip(reader.as_mut(), offset as usize))?;
+
+        loop {
+            let chunk = block_on(reader.fill_buf())?;
+            if chunk.is_empty() {
+                break;
+            }
+            let n = chunk.len();
+            remote.write_all(chunk)?;
+            reader.consume_unpin(n);
+        }
+        remote.flush()?;
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,42 @@
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use surf::{RequestBuilder, Url};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthToken {
+    pub base: Url,
+    pub headers: Vec<(String, String)>,
+}
+
+impl AuthToken {
+    pub fn new(base: &str) -> Result<AuthToken> {
+        let mut base = Url::parse(base)?;
+        if !matches!(base.scheme(), "http" | "https") {
+            return Err(anyhow!("unable to recognize the scheme {}.", base.scheme()));
+        }
+        if !base.path().ends_with('/') {
+            let path = format!("{}/", base.path());
+            base.set_path(path.as_str());
+        }
+
+        Ok(AuthToken {
+            base,
+            headers: vec![],
+        })
+    }
+
+    pub fn basic(mut self, username: &str, password: &str) -> AuthToken {
+        let encoded = STANDARD.encode(format!("{}:{}", username, password));
+        self.headers
+            .push((String::from("Authorization"), format!("Basic {}", encoded)));
+        self
+    }
+
+    pub(super) fn authorize(&self, mut req: RequestBuilder) -> RequestBuilder {
+        for (k, v) in self.headers.iter() {
+            req = req.header(k.as_str(), v.as_str());
+        }
+        req
+    }
+}
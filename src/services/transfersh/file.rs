@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Error, Result};
+use surf::Url;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct File {
+    url: Url,
+    delete_token: Option<String>,
+}
+
+impl File {
+    pub fn new(url: Url, delete_token: Option<String>) -> File {
+        File { url, delete_token }
+    }
+
+    pub fn get_url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn get_delete_token(&self) -> Option<&str> {
+        self.delete_token.as_deref()
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        let url = Url::parse(src)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(anyhow!("unable to recognize the scheme {}.", url.scheme()));
+        }
+
+        Ok(File {
+            url,
+            delete_token: None,
+        })
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+// The delete token is a secret, so it stays out of the shareable link.
+impl From<File> for String {
+    fn from(file: File) -> String {
+        file.url.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_tryfrom_str() -> Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            url: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "https://transfer.sh/Ab12/a.txt",
+                url: Some("https://transfer.sh/Ab12/a.txt"),
+            },
+            TestCase {
+                src: "http://127.0.0.1:8080/Ab12/a.txt",
+                url: Some("http://127.0.0.1:8080/Ab12/a.txt"),
+            },
+            TestCase {
+                src: "ftp://transfer.sh/Ab12/a.txt",
+                url: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let file = File::try_from(testcase.src);
+            match testcase.url {
+                Some(v) => {
+                    let file = file?;
+                    assert_eq!(file.get_url().as_str(), v);
+                    assert_eq!(file.get_delete_token(), None);
+                    let uri: String = file.into();
+                    assert_eq!(uri, testcase.src);
+                }
+                None => assert!(file.is_err()),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn file_into_string() -> Result<()> {
+        let url = Url::parse("https://transfer.sh/Ab12/a.txt")?;
+        let file = File::new(url, Some(String::from("XyZ9")));
+        let uri: String = file.into();
+        assert_eq!(uri, "https://transfer.sh/Ab12/a.txt");
+        Ok(())
+    }
+}
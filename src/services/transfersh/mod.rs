@@ -0,0 +1,230 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod download;
+use download::*;
+mod upload;
+use upload::*;
+
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, Metadata, Probe, Service, Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use surf::http::StatusCode;
+
+pub struct TransferSh {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UploadSetting {
+    pub max_days: Option<u32>,
+    pub max_downloads: Option<u32>,
+}
+
+impl Service for TransferSh {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for TransferSh {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, auth_token, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for TransferSh {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(&file, auth_token, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for TransferSh {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        match probe_file(file, auth_token).await? {
+            (_, Some(metadata)) => Ok(metadata),
+            (status, None) => Err(anyhow::anyhow!("unexpected status {}.", status)),
+        }
+    }
+}
+
+#[async_trait]
+impl Probe for TransferSh {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        let status = match probe_file(file, auth_token).await? {
+            (_, Some(metadata)) => LinkStatus::Alive(metadata),
+            (StatusCode::NotFound | StatusCode::Gone, None) => LinkStatus::Removed,
+            (StatusCode::Unauthorized | StatusCode::Forbidden, None) => LinkStatus::Private,
+            (v, None) => LinkStatus::Unknown(format!("unexpected status {}.", v)),
+        };
+
+        Ok(status)
+    }
+}
+
+#[async_trait]
+impl Delete for TransferSh {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        delete_file(&file, auth_token).await
+    }
+}
+
+#[async_trait]
+impl Upload for TransferSh {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::MAX
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        upload_file(name, reader, len, &setting, auth_token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, DownloadSetting, TransferSh, UploadSetting};
+    use crate::utils::{roundtrip, serve_with_base, MockResponse};
+    use crate::{Delete, FileMetadata, Upload};
+    use async_std::io::Cursor;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    struct Stored {
+        data: Vec<u8>,
+        max_downloads: Option<String>,
+        max_days: Option<String>,
+    }
+
+    fn server(store: Arc<Mutex<BTreeMap<String, Stored>>>) -> String {
+        serve_with_base(move |base, req| {
+            if req.header("authorization") != Some("Basic dXNlcjpwYXNz") {
+                return MockResponse::new(401);
+            }
+            let mut store = store.lock().unwrap();
+            let segments: Vec<&str> = req.path.trim_start_matches('/').split('/').collect();
+            match (req.method.as_str(), segments.as_slice()) {
+                ("PUT", [name]) => {
+                    let token = format!("t{}", store.len());
+                    let path = format!("/{}/{}", token, name);
+                    store.insert(
+                        path.clone(),
+                        Stored {
+                            data: req.body.clone(),
+                            max_downloads: req.header("max-downloads").map(|v| v.to_string()),
+                            max_days: req.header("max-days").map(|v| v.to_string()),
+                        },
+                    );
+                    let url = format!("{}{}", base, path);
+                    MockResponse::new(200)
+                        .header("X-Url-Delete", format!("{}/d{}", url, token).as_str())
+                        .body(format!("{}\n", url))
+                }
+                ("DELETE", [token, name, delete]) => {
+                    let path = format!("/{}/{}", token, name);
+                    if *delete != format!("d{}", token) || store.remove(&path).is_none() {
+                        return MockResponse::new(404);
+                    }
+                    MockResponse::new(200)
+                }
+                ("GET", [token, name]) => match store.get(&format!("/{}/{}", token, name)) {
+                    Some(v) => MockResponse::ranged(&req, &v.data),
+                    None => MockResponse::new(404),
+                },
+                _ => MockResponse::new(405),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn transfersh_roundtrip() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            name: &'a str,
+            setting: UploadSetting,
+            max_days: Option<&'a str>,
+            max_downloads: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                name: "a b.txt",
+                setting: UploadSetting::default(),
+                max_days: None,
+                max_downloads: None,
+            },
+            TestCase {
+                name: "b.txt",
+                setting: UploadSetting {
+                    max_days: Some(3),
+                    max_downloads: Some(5),
+                },
+                max_days: Some("3"),
+                max_downloads: Some("5"),
+            },
+        ];
+
+        let store = Arc::new(Mutex::new(BTreeMap::new()));
+        let base = server(store.clone());
+        let auth_token = AuthToken::new(base.as_str())?.basic("user", "pass");
+        for testcase in testcases {
+            let reader = Box::new(Cursor::new("abcdefgh"));
+            let file = TransferSh::upload(
+                testcase.name,
+                reader,
+                Some(8),
+                testcase.setting,
+                &auth_token,
+            )
+            .await?;
+            assert!(file.get_delete_token().is_some());
+            {
+                let store = store.lock().unwrap();
+                let stored = store.get(file.get_url().path()).unwrap();
+                assert_eq!(stored.max_days.as_deref(), testcase.max_days);
+                assert_eq!(stored.max_downloads.as_deref(), testcase.max_downloads);
+            }
+
+            let metadata = FileMetadata {
+                name: testcase.name.to_string(),
+                len: Some(8),
+            };
+            roundtrip::<TransferSh>(file, DownloadSetting {}, &auth_token, b"abcdefgh", metadata)
+                .await?;
+        }
+
+        let file = super::File::try_from(format!("{}/t9/a.txt", base))?;
+        assert!(TransferSh::delete(file, &auth_token).await.is_err());
+
+        Ok(())
+    }
+}
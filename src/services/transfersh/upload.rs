@@ -0,0 +1,116 @@
+use super::{AuthToken, File, UploadSetting};
+use crate::utils::send;
+use futures::AsyncBufRead;
+use surf::http::Method;
+use surf::{Body, Client, Url};
+
+pub async fn upload_file<'a>(
+    name: &'a str,
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    len: Option<usize>,
+    setting: &'a UploadSetting,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<File> {
+    let client = Client::new();
+
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(anyhow::anyhow!("unable to recognize the name {}.", name));
+    }
+    let url = {
+        let mut url = auth_token.base.clone();
+        match url.path_segments_mut() {
+            Ok(mut segments) => {
+                segments.pop_if_empty().push(name);
+            }
+            Err(_) => return Err(anyhow::anyhow!("unable to recognize the base url.")),
+        }
+        url
+    };
+
+    let mut headers = vec![];
+    if let Some(v) = setting.max_days {
+        headers.push(("Max-Days", v.to_string()));
+    }
+    if let Some(v) = setting.max_downloads {
+        headers.push(("Max-Downloads", v.to_string()));
+    }
+    let body = Body::from_reader(reader, len);
+    let mut res = send(&client, Method::Put, &url, headers, Some(body), |req| {
+        auth_token.authorize(req)
+    })
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    let delete_url = res.header("X-Url-Delete").map(|v| v.last().to_string());
+    let problem = match res.body_string().await {
+        Ok(v) => v,
+        Err(e) => return Err(e.into_inner()),
+    };
+    let url = Url::parse(problem.trim())?;
+    let delete_token = match delete_url {
+        Some(v) => Some(get_delete_token(&url, v.as_str())?),
+        None => None,
+    };
+
+    Ok(File::new(url, delete_token))
+}
+
+fn get_delete_token(url: &Url, delete_url: &str) -> anyhow::Result<String> {
+    let delete_url = Url::parse(delete_url)?;
+    let token = delete_url
+        .path()
+        .strip_prefix(url.path())
+        .and_then(|v| v.strip_prefix('/'))
+        .filter(|v| !v.is_empty() && !v.contains('/'));
+    match token {
+        Some(v) => Ok(v.to_string()),
+        None => Err(anyhow::anyhow!(
+            "unable to recognize the delete url {}.",
+            delete_url
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use surf::Url;
+
+    #[test]
+    fn get_delete_token() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            url: &'a str,
+            delete_url: &'a str,
+            token: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                url: "https://transfer.sh/Ab12/a%20b.txt",
+                delete_url: "https://transfer.sh/Ab12/a%20b.txt/XyZ9",
+                token: Some("XyZ9"),
+            },
+            TestCase {
+                url: "https://transfer.sh/Ab12/a.txt",
+                delete_url: "https://transfer.sh/Cd34/a.txt/XyZ9",
+                token: None,
+            },
+            TestCase {
+                url: "https://transfer.sh/Ab12/a.txt",
+                delete_url: "https://transfer.sh/Ab12/a.txt/",
+                token: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let url = Url::parse(testcase.url)?;
+            let token = super::get_delete_token(&url, testcase.delete_url);
+            match testcase.token {
+                Some(v) => assert_eq!(token?, v),
+                None => assert!(token.is_err()),
+            }
+        }
+        Ok(())
+    }
+}
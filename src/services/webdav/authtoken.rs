@@ -0,0 +1,240 @@
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use md5::{Digest, Md5};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use surf::Url;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Credential {
+    Anonymous,
+    Basic { username: String, password: String },
+    Digest { username: String, password: String },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(super) struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    qop: Option<String>,
+    nc: u32,
+}
+
+impl Challenge {
+    pub fn parse(header: &str) -> Option<Challenge> {
+        let params = header.strip_prefix("Digest ")?;
+        let params = parse_params(params);
+        Some(Challenge {
+            realm: params.get("realm")?.clone(),
+            nonce: params.get("nonce")?.clone(),
+            opaque: params.get("opaque").cloned(),
+            qop: params
+                .get("qop")
+                .filter(|v| v.split(',').any(|v| v.trim() == "auth"))
+                .map(|_| String::from("auth")),
+            nc: 0,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthToken {
+    pub base: Url,
+    pub credential: Credential,
+    pub(super) challenge: Arc<Mutex<Option<Challenge>>>,
+}
+
+impl AuthToken {
+    pub fn new(base: &str, credential: Credential) -> Result<AuthToken> {
+        let mut base = Url::parse(base)?;
+        if !matches!(base.scheme(), "http" | "https") {
+            return Err(anyhow!("unable to recognize the scheme {}.", base.scheme()));
+        }
+        if !base.path().ends_with('/') {
+            let path = format!("{}/", base.path());
+            base.set_path(path.as_str());
+        }
+
+        Ok(AuthToken {
+            base,
+            credential,
+            challenge: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    pub(super) fn needs_challenge(&self) -> bool {
+        matches!(self.credential, Credential::Digest { .. })
+            && self.challenge.lock().unwrap().is_none()
+    }
+
+    pub(super) fn set_challenge(&self, header: &str) -> bool {
+        let challenge = Challenge::parse(header);
+        let found = challenge.is_some();
+        *self.challenge.lock().unwrap() = challenge;
+        found
+    }
+
+    pub(super) fn authorization(&self, method: &str, url: &Url) -> Option<String> {
+        match &self.credential {
+            Credential::Anonymous => None,
+            Credential::Basic { username, password } => {
+                let encoded = STANDARD.encode(format!("{}:{}", username, password));
+                Some(format!("Basic {}", encoded))
+            }
+            Credential::Digest { username, password } => {
+                let mut challenge = self.challenge.lock().unwrap();
+                let challenge = challenge.as_mut()?;
+                challenge.nc += 1;
+
+                let uri = match url.query() {
+                    Some(query) => format!("{}?{}", url.path(), query),
+                    None => url.path().to_string(),
+                };
+                let cnonce = format!("{:016x}", rand::random::<u64>());
+                let nc = format!("{:08x}", challenge.nc);
+                let response = digest_response(
+                    username,
+                    password,
+                    challenge,
+                    method,
+                    uri.as_str(),
+                    nc.as_str(),
+                    cnonce.as_str(),
+                );
+
+                let mut header = format!(
+                    "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", response=\"{}\"",
+                    username, challenge.realm, challenge.nonce, uri, response
+                );
+                if let Some(qop) = challenge.qop.as_ref() {
+                    header.push_str(
+                        format!(", qop={}, nc={}, cnonce=\"{}\"", qop, nc, cnonce).as_str(),
+                    );
+                }
+                if let Some(opaque) = challenge.opaque.as_ref() {
+                    header.push_str(format!(", opaque=\"{}\"", opaque).as_str());
+                }
+                Some(header)
+            }
+        }
+    }
+}
+
+pub(super) fn parse_params(src: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut rest = src.trim();
+    while !rest.is_empty() {
+        let (key, after) = match rest.split_once('=') {
+            Some(v) => v,
+            None => break,
+        };
+        let key = key.trim().trim_start_matches(',').trim().to_lowercase();
+        let after = after.trim_start();
+        let (value, after) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, after)) => (value, after),
+                None => (quoted, ""),
+            },
+            None => match after.split_once(',') {
+                Some((value, after)) => (value.trim(), after),
+                None => (after.trim(), ""),
+            },
+        };
+        params.insert(key, value.to_string());
+        rest = after.trim_start().trim_start_matches(',').trim_start();
+    }
+
+    params
+}
+
+pub(super) fn md5_hex(src: &str) -> String {
+    let mut hasher = Md5::new();
+    hasher.update(src.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|v| format!("{:02x}", v))
+        .collect()
+}
+
+fn digest_response(
+    username: &str,
+    password: &str,
+    challenge: &Challenge,
+    method: &str,
+    uri: &str,
+    nc: &str,
+    cnonce: &str,
+) -> String {
+    let ha1 = md5_hex(format!("{}:{}:{}", username, challenge.realm, password).as_str());
+    let ha2 = md5_hex(format!("{}:{}", method, uri).as_str());
+    match challenge.qop.as_ref() {
+        Some(qop) => md5_hex(
+            format!(
+                "{}:{}:{}:{}:{}:{}",
+                ha1, challenge.nonce, nc, cnonce, qop, ha2
+            )
+            .as_str(),
+        ),
+        None => md5_hex(format!("{}:{}:{}", ha1, challenge.nonce, ha2).as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_params_test() -> Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            params: Vec<(&'a str, &'a str)>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "realm=\"dav, files\", nonce=\"abc\", qop=\"auth,auth-int\", algorithm=MD5",
+                params: vec![
+                    ("realm", "dav, files"),
+                    ("nonce", "abc"),
+                    ("qop", "auth,auth-int"),
+                    ("algorithm", "MD5"),
+                ],
+            },
+            TestCase {
+                src: "username=\"a\",uri=\"/dav/a.txt\",nc=00000001",
+                params: vec![("username", "a"), ("uri", "/dav/a.txt"), ("nc", "00000001")],
+            },
+        ];
+
+        for testcase in testcases {
+            let params = parse_params(testcase.src);
+            assert_eq!(params.len(), testcase.params.len());
+            for (k, v) in testcase.params {
+                assert_eq!(params.get(k).map(|v| v.as_str()), Some(v));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn digest_response_test() -> Result<()> {
+        let challenge = Challenge::parse(
+            "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+        )
+        .unwrap();
+        let response = digest_response(
+            "Mufasa",
+            "Circle Of Life",
+            &challenge,
+            "GET",
+            "/dir/index.html",
+            "00000001",
+            "0a4f113b",
+        );
+        assert_eq!(response, "6629fae49393a05397450978507c4ef1");
+        Ok(())
+    }
+}
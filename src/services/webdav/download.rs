@@ -0,0 +1,37 @@
+use super::{send, AuthToken, Payload};
+use crate::utils::skip;
+use futures::AsyncBufRead;
+use surf::http::{Method, StatusCode};
+use surf::{Client, Url};
+
+pub async fn download_file<'a>(
+    url: &'a Url,
+    auth_token: &'a AuthToken,
+    offset: usize,
+) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+    let client = Client::new();
+
+    let mut headers = vec![];
+    if offset > 0 {
+        headers.push(("Range", format!("bytes={}-", offset)));
+    }
+    let mut res = send(
+        &client,
+        Method::Get,
+        url,
+        headers,
+        Payload::Empty,
+        auth_token,
+    )
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    let mut reader = res.take_body().into_reader();
+    if offset > 0 && res.status() != StatusCode::PartialContent {
+        skip(&mut reader, offset).await?;
+    }
+
+    Ok(reader)
+}
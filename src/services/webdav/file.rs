@@ -0,0 +1,45 @@
+use anyhow::{anyhow, Error, Result};
+use surf::Url;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct File {
+    url: Url,
+}
+
+impl File {
+    pub fn get_url(&self) -> &Url {
+        &self.url
+    }
+}
+
+impl TryFrom<&str> for File {
+    type Error = Error;
+
+    fn try_from(src: &str) -> Result<Self> {
+        let url = Url::parse(src)?;
+        match url.scheme() {
+            "http" | "https" => Ok(File { url }),
+            scheme => Err(anyhow!("unable to recognize the scheme {}.", scheme)),
+        }
+    }
+}
+
+impl TryFrom<String> for File {
+    type Error = Error;
+
+    fn try_from(src: String) -> Result<Self> {
+        File::try_from(src.as_str())
+    }
+}
+
+impl From<Url> for File {
+    fn from(url: Url) -> File {
+        File { url }
+    }
+}
+
+impl From<File> for String {
+    fn from(file: File) -> String {
+        file.url.to_string()
+    }
+}
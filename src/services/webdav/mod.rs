@@ -0,0 +1,396 @@
+mod authtoken;
+pub use authtoken::*;
+mod file;
+pub use file::*;
+mod download;
+use download::*;
+mod propfind;
+use propfind::*;
+mod request;
+use request::*;
+mod upload;
+use upload::*;
+
+use crate::utils::percent_decode;
+use crate::{
+    Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Service,
+    Upload,
+};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use surf::http::StatusCode;
+use surf::Url;
+
+pub struct Webdav {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DownloadSetting {}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UploadSetting {}
+
+fn to_metadata(url: &Url, entry: PropfindEntry) -> FileMetadata {
+    let name = entry.name.unwrap_or_else(|| {
+        url.path_segments()
+            .and_then(|mut v| v.next_back())
+            .and_then(percent_decode)
+            .unwrap_or_default()
+    });
+    FileMetadata {
+        name,
+        len: entry.len,
+    }
+}
+
+impl Service for Webdav {
+    type AuthToken = AuthToken;
+    type File = File;
+}
+
+#[async_trait]
+impl Download for Webdav {
+    type DownloadSetting = DownloadSetting;
+
+    async fn download<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(file.get_url(), auth_token, 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Webdav {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(file.get_url(), auth_token, offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for Webdav {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        let (status, entries) = propfind(file.get_url(), 0, auth_token).await?;
+        if status != StatusCode::MultiStatus {
+            return Err(anyhow::anyhow!("unexpected status {}.", status));
+        }
+        match entries.into_iter().next() {
+            Some(entry) => Ok(to_metadata(file.get_url(), entry)),
+            None => Err(anyhow::anyhow!("file does not exist.")),
+        }
+    }
+}
+
+#[async_trait]
+impl Probe for Webdav {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        let (status, entries) = propfind(file.get_url(), 0, auth_token).await?;
+        let status = match status {
+            StatusCode::MultiStatus => match entries.into_iter().next() {
+                Some(entry) => LinkStatus::Alive(to_metadata(file.get_url(), entry)),
+                None => LinkStatus::Removed,
+            },
+            StatusCode::NotFound | StatusCode::Gone => LinkStatus::Removed,
+            StatusCode::Unauthorized | StatusCode::Forbidden => LinkStatus::Private,
+            v => LinkStatus::Unknown(format!("unexpected status {}.", v)),
+        };
+
+        Ok(status)
+    }
+}
+
+#[async_trait]
+impl Delete for Webdav {
+    async fn delete<'a>(file: Self::File, auth_token: &'a Self::AuthToken) -> anyhow::Result<()> {
+        delete_file(file.get_url(), auth_token).await
+    }
+}
+
+#[async_trait]
+impl List for Webdav {
+    async fn list<'a>(
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Vec<(Self::File, FileMetadata)>> {
+        let (status, entries) = propfind(&auth_token.base, 1, auth_token).await?;
+        if status != StatusCode::MultiStatus {
+            return Err(anyhow::anyhow!("unexpected status {}.", status));
+        }
+
+        let mut files = vec![];
+        for entry in entries {
+            let url = auth_token.base.join(entry.href.as_str())?;
+            if entry.collection || url.path() == auth_token.base.path() {
+                continue;
+            }
+            let metadata = to_metadata(&url, entry);
+            files.push((File::from(url), metadata));
+        }
+        Ok(files)
+    }
+}
+
+#[async_trait]
+impl Upload for Webdav {
+    type UploadSetting = UploadSetting;
+
+    fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+        usize::MAX
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        _setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let url = upload_file(name, reader, len, auth_token).await?;
+        Ok(File::from(url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::authtoken::{md5_hex, parse_params};
+    use super::{AuthToken, Credential, DownloadSetting, UploadSetting, Webdav};
+    use crate::utils::{serve, MockRequest, MockResponse};
+    use crate::{
+        Delete, Download, DownloadRange, FileMetadata, LinkStatus, List, Metadata, Probe, Upload,
+    };
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    const REALM: &str = "dav";
+    const NONCE: &str = "dcd98b7102dd2f0e8b11d0f600bfb0c093";
+
+    fn authorized(req: &MockRequest, digest: bool) -> bool {
+        let header = match req.header("authorization") {
+            Some(v) => v,
+            None => return false,
+        };
+        if !digest {
+            return header == "Basic dXNlcjpwYXNz";
+        }
+
+        let params = match header.strip_prefix("Digest ") {
+            Some(v) => parse_params(v),
+            None => return false,
+        };
+        let get = |k: &str| params.get(k).cloned().unwrap_or_default();
+        let ha1 = md5_hex(format!("{}:{}:{}", get("username"), REALM, "pass").as_str());
+        let ha2 = md5_hex(format!("{}:{}", req.method, get("uri")).as_str());
+        let response = md5_hex(
+            format!(
+                "{}:{}:{}:{}:{}:{}",
+                ha1,
+                NONCE,
+                get("nc"),
+                get("cnonce"),
+                get("qop"),
+                ha2
+            )
+            .as_str(),
+        );
+        get("uri") == req.path && get("opaque") == "5ccc" && get("response") == response
+    }
+
+    fn server(digest: bool) -> String {
+        let store: Arc<Mutex<BTreeMap<String, Vec<u8>>>> = Arc::new(Mutex::new(BTreeMap::new()));
+        serve(move |req| {
+            if !authorized(&req, digest) {
+                let challenge = match digest {
+                    true => format!(
+                        "Digest realm=\"{}\", nonce=\"{}\", qop=\"auth\", opaque=\"5ccc\"",
+                        REALM, NONCE
+                    ),
+                    false => format!("Basic realm=\"{}\"", REALM),
+                };
+                return MockResponse::new(401).header("WWW-Authenticate", challenge.as_str());
+            }
+
+            let mut store = store.lock().unwrap();
+            match req.method.as_str() {
+                "OPTIONS" => MockResponse::new(200).header("DAV", "1"),
+                "PUT" => {
+                    store.insert(req.path.clone(), req.body.clone());
+                    MockResponse::new(201)
+                }
+                "DELETE" => match store.remove(&req.path) {
+                    Some(_) => MockResponse::new(204),
+                    None => MockResponse::new(404),
+                },
+                "GET" => {
+                    let data = match store.get(&req.path) {
+                        Some(v) => v,
+                        None => return MockResponse::new(404),
+                    };
+                    let offset = req
+                        .header("range")
+                        .and_then(|v| v.strip_prefix("bytes="))
+                        .and_then(|v| v.strip_suffix('-'))
+                        .and_then(|v| v.parse::<usize>().ok());
+                    match offset {
+                        Some(offset) => MockResponse::new(206)
+                            .header(
+                                "Content-Range",
+                                format!("bytes {}-{}/{}", offset, data.len() - 1, data.len())
+                                    .as_str(),
+                            )
+                            .body(&data[offset..]),
+                        None => MockResponse::new(200).body(data.clone()),
+                    }
+                }
+                "PROPFIND" => {
+                    let response = |href: &str, len: Option<usize>| {
+                        match len {
+                        Some(len) => format!("<D:response><D:href>{}</D:href><D:propstat><D:prop><D:getcontentlength>{}</D:getcontentlength><D:resourcetype/></D:prop></D:propstat></D:response>", href, len),
+                        None => format!("<D:response><D:href>{}</D:href><D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop></D:propstat></D:response>", href),
+                    }
+                    };
+                    let mut responses = vec![];
+                    if req.path.ends_with('/') {
+                        responses.push(response(req.path.as_str(), None));
+                        if req.header("depth") == Some("1") {
+                            for (href, data) in store.iter() {
+                                responses.push(response(href.as_str(), Some(data.len())));
+                            }
+                        }
+                    } else {
+                        match store.get(&req.path) {
+                            Some(data) => {
+                                responses.push(response(req.path.as_str(), Some(data.len())))
+                            }
+                            None => return MockResponse::new(404),
+                        }
+                    }
+                    let body = format!(
+                        "<?xml version=\"1.0\" encoding=\"utf-8\"?><D:multistatus xmlns:D=\"DAV:\">{}</D:multistatus>",
+                        responses.concat()
+                    );
+                    MockResponse::new(207)
+                        .header("Content-Type", "application/xml; charset=utf-8")
+                        .body(body)
+                }
+                _ => MockResponse::new(405),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn webdav_roundtrip() -> anyhow::Result<()> {
+        struct TestCase {
+            digest: bool,
+            credential: Credential,
+            authorized: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                digest: false,
+                credential: Credential::Basic {
+                    username: String::from("user"),
+                    password: String::from("pass"),
+                },
+                authorized: true,
+            },
+            TestCase {
+                digest: false,
+                credential: Credential::Basic {
+                    username: String::from("user"),
+                    password: String::from("wrong"),
+                },
+                authorized: false,
+            },
+            TestCase {
+                digest: true,
+                credential: Credential::Digest {
+                    username: String::from("user"),
+                    password: String::from("pass"),
+                },
+                authorized: true,
+            },
+            TestCase {
+                digest: false,
+                credential: Credential::Anonymous,
+                authorized: false,
+            },
+        ];
+
+        for testcase in testcases {
+            let base = format!("{}/dav", server(testcase.digest));
+            let auth_token = AuthToken::new(base.as_str(), testcase.credential)?;
+
+            let reader = Box::new(Cursor::new("abcdefgh"));
+            let result =
+                Webdav::upload("a b.txt", reader, Some(8), UploadSetting {}, &auth_token).await;
+            if !testcase.authorized {
+                assert!(result.is_err());
+                continue;
+            }
+            let file = result?;
+            assert_eq!(String::from(file.clone()), format!("{}/a%20b.txt", base));
+
+            let reader = Box::new(Cursor::new("xyz"));
+            Webdav::upload("c.bin", reader, Some(3), UploadSetting {}, &auth_token).await?;
+
+            let mut data = String::new();
+            Webdav::download(file.clone(), DownloadSetting {}, &auth_token)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, "abcdefgh");
+
+            let mut data = String::new();
+            Webdav::download_range(file.clone(), DownloadSetting {}, &auth_token, 5)
+                .await?
+                .read_to_string(&mut data)
+                .await?;
+            assert_eq!(data, "fgh");
+
+            let metadata = FileMetadata {
+                name: String::from("a b.txt"),
+                len: Some(8),
+            };
+            assert_eq!(Webdav::metadata(&file, &auth_token).await?, metadata);
+            assert_eq!(
+                Webdav::probe(&file, &auth_token).await?,
+                LinkStatus::Alive(metadata)
+            );
+
+            let listed: Vec<(String, Option<usize>)> = Webdav::list(&auth_token)
+                .await?
+                .into_iter()
+                .map(|(file, metadata)| (file.into(), metadata.len))
+                .collect();
+            assert_eq!(
+                listed,
+                vec![
+                    (format!("{}/a%20b.txt", base), Some(8)),
+                    (format!("{}/c.bin", base), Some(3)),
+                ]
+            );
+
+            Webdav::delete(file.clone(), &auth_token).await?;
+            assert_eq!(
+                Webdav::probe(&file, &auth_token).await?,
+                LinkStatus::Removed
+            );
+            assert!(Webdav::delete(file, &auth_token).await.is_err());
+        }
+
+        Ok(())
+    }
+}
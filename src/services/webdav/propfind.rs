@@ -0,0 +1,109 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PropfindEntry {
+    pub href: String,
+    pub name: Option<String>,
+    pub len: Option<usize>,
+    pub collection: bool,
+}
+
+pub const PROPFIND_BODY: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<d:propfind xmlns:d=\"DAV:\"><d:prop><d:displayname/><d:getcontentlength/><d:resourcetype/></d:prop></d:propfind>";
+
+pub fn parse_multistatus(xml: &str) -> anyhow::Result<Vec<PropfindEntry>> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut entries = vec![];
+    let mut current: Option<PropfindEntry> = None;
+    let mut element = String::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                element = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                match element.as_str() {
+                    "response" => current = Some(PropfindEntry::default()),
+                    "collection" => {
+                        if let Some(entry) = current.as_mut() {
+                            entry.collection = true;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"collection" => {
+                if let Some(entry) = current.as_mut() {
+                    entry.collection = true;
+                }
+            }
+            Event::Text(e) => {
+                let text = e.unescape()?.to_string();
+                if let Some(entry) = current.as_mut() {
+                    match element.as_str() {
+                        "href" => entry.href = text,
+                        "displayname" if !text.is_empty() => entry.name = Some(text),
+                        "getcontentlength" => entry.len = text.trim().parse().ok(),
+                        _ => {}
+                    }
+                }
+            }
+            Event::End(e) => {
+                if e.local_name().as_ref() == b"response" {
+                    if let Some(entry) = current.take() {
+                        entries.push(entry);
+                    }
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PropfindEntry;
+
+    #[test]
+    fn parse_multistatus_test() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            problem: &'a str,
+            solution: Vec<PropfindEntry>,
+        }
+        let function = super::parse_multistatus;
+
+        let testcases = [TestCase {
+            problem: "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<D:multistatus xmlns:D=\"DAV:\">\n<D:response><D:href>/dav/</D:href><D:propstat><D:prop><D:displayname></D:displayname><D:resourcetype><D:collection/></D:resourcetype></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>\n<D:response><D:href>/dav/a%20b.txt</D:href><D:propstat><D:prop><D:displayname>a b.txt</D:displayname><D:getcontentlength>4</D:getcontentlength><D:resourcetype/></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>\n<d:response xmlns:d=\"DAV:\"><d:href>/dav/c.bin</d:href><d:propstat><d:prop><d:getcontentlength>10</d:getcontentlength></d:prop></d:propstat></d:response>\n</D:multistatus>",
+            solution: vec![
+                PropfindEntry {
+                    href: String::from("/dav/"),
+                    name: None,
+                    len: None,
+                    collection: true,
+                },
+                PropfindEntry {
+                    href: String::from("/dav/a%20b.txt"),
+                    name: Some(String::from("a b.txt")),
+                    len: Some(4),
+                    collection: false,
+                },
+                PropfindEntry {
+                    href: String::from("/dav/c.bin"),
+                    name: None,
+                    len: Some(10),
+                    collection: false,
+                },
+            ],
+        }];
+
+        for testcase in testcases {
+            let solution = function(testcase.problem);
+            assert_eq!(solution?, testcase.solution);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use super::{parse_multistatus, AuthToken, PropfindEntry, PROPFIND_BODY};
+use surf::http::{Method, StatusCode};
+use surf::{Body, Client, Response, Url};
+
+pub enum Payload<'a> {
+    Empty,
+    Xml(&'a str),
+    Stream(Body),
+}
+
+pub async fn send<'a>(
+    client: &'a Client,
+    method: Method,
+    url: &'a Url,
+    headers: Vec<(&'a str, String)>,
+    payload: Payload<'a>,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<Response> {
+    if auth_token.needs_challenge() {
+        refresh_challenge(client, auth_token).await?;
+    }
+
+    let mut payload = Some(payload);
+    let mut retried = false;
+    loop {
+        let (body, replayable) = match payload.take() {
+            Some(Payload::Empty) | None => (None, true),
+            Some(Payload::Xml(v)) => {
+                payload = Some(Payload::Xml(v));
+                (Some(Body::from_string(v.to_string())), true)
+            }
+            Some(Payload::Stream(v)) => (Some(v), false),
+        };
+
+        let authorization = auth_token.authorization(method.to_string().as_str(), url);
+        let res =
+            crate::utils::send(
+                client,
+                method,
+                url,
+                headers.clone(),
+                body,
+                |req| match authorization {
+                    Some(v) => req.header("Authorization", v),
+                    None => req,
+                },
+            )
+            .await?;
+
+        if res.status() == StatusCode::Unauthorized && replayable && !retried {
+            if let Some(header) = res.header("WWW-Authenticate") {
+                if auth_token.set_challenge(header.last().as_str()) {
+                    retried = true;
+                    continue;
+                }
+            }
+        }
+
+        return Ok(res);
+    }
+}
+
+async fn refresh_challenge<'a>(
+    client: &'a Client,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<()> {
+    let res = crate::utils::send(
+        client,
+        Method::Options,
+        &auth_token.base,
+        vec![],
+        None,
+        |req| req,
+    )
+    .await?;
+
+    match res.header("WWW-Authenticate") {
+        Some(header) if auth_token.set_challenge(header.last().as_str()) => Ok(()),
+        _ => Err(anyhow::anyhow!("unable to recognize the digest challenge.")),
+    }
+}
+
+pub async fn propfind<'a>(
+    url: &'a Url,
+    depth: usize,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<(StatusCode, Vec<PropfindEntry>)> {
+    let client = Client::new();
+
+    let headers = vec![
+        ("Depth", depth.to_string()),
+        (
+            "Content-Type",
+            String::from("application/xml; charset=utf-8"),
+        ),
+    ];
+    let mut res = send(
+        &client,
+        Method::PropFind,
+        url,
+        headers,
+        Payload::Xml(PROPFIND_BODY),
+        auth_token,
+    )
+    .await?;
+    if res.status() != StatusCode::MultiStatus {
+        return Ok((res.status(), vec![]));
+    }
+
+    let problem = match res.body_string().await {
+        Ok(v) => v,
+        Err(e) => return Err(e.into_inner()),
+    };
+
+    Ok((res.status(), parse_multistatus(problem.as_str())?))
+}
+
+pub async fn delete_file<'a>(url: &'a Url, auth_token: &'a AuthToken) -> anyhow::Result<()> {
+    let client = Client::new();
+
+    let res = send(
+        &client,
+        Method::Delete,
+        url,
+        vec![],
+        Payload::Empty,
+        auth_token,
+    )
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    Ok(())
+}
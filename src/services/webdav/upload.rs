@@ -0,0 +1,43 @@
+use super::{send, AuthToken, Payload};
+use futures::AsyncBufRead;
+use surf::http::Method;
+use surf::{Body, Client, Url};
+
+pub async fn upload_file<'a>(
+    name: &'a str,
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    len: Option<usize>,
+    auth_token: &'a AuthToken,
+) -> anyhow::Result<Url> {
+    let client = Client::new();
+
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(anyhow::anyhow!("unable to recognize the name {}.", name));
+    }
+    let url = {
+        let mut url = auth_token.base.clone();
+        match url.path_segments_mut() {
+            Ok(mut segments) => {
+                segments.pop_if_empty().push(name);
+            }
+            Err(_) => return Err(anyhow::anyhow!("unable to recognize the base url.")),
+        }
+        url
+    };
+
+    let body = Body::from_reader(reader, len);
+    let res = send(
+        &client,
+        Method::Put,
+        &url,
+        vec![],
+        Payload::Stream(body),
+        auth_token,
+    )
+    .await?;
+    if !res.status().is_success() {
+        return Err(anyhow::anyhow!("unexpected status {}.", res.status()));
+    }
+
+    Ok(url)
+}
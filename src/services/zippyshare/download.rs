@@ -1,25 +1,33 @@
+use crate::utils::skip;
+use crate::{FileMetadata, LinkStatus};
 use futures::{AsyncBufRead, AsyncReadExt};
 use regex::Regex;
-use surf::http::Method;
+use surf::http::{Method, StatusCode};
 use surf::{Client, Request, Url};
 
+const FILE_DOES_NOT_EXIST: &str = "File does not exist on this server";
+
 pub async fn download_file<'a>(
     server_id: &'a str,
     file_id: &'a str,
+    offset: usize,
 ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
     let client = Client::new();
 
-    let (download_id, filename) = {
+    let (download_id, filename) = get_download_page(&client, server_id, file_id).await?;
+
+    let download_reader = {
         let req = {
-            let url = {
-                let uri = format!(
-                    "https://www{}.zippyshare.com/v/{}/file.html",
-                    server_id, file_id
-                );
-                let url = Url::parse(uri.as_str())?;
-                url
-            };
-            Request::builder(Method::Get, url).build()
+            let uri = format!(
+                "https://www{}.zippyshare.com/d/{}/{}/{}",
+                server_id, file_id, download_id, filename
+            );
+            let url = Url::parse(uri.as_str())?;
+            let mut req = Request::builder(Method::Get, url);
+            if offset > 0 {
+                req = req.header("Range", format!("bytes={}-", offset));
+            }
+            req.build()
         };
 
         let mut res = match client.send(req).await {
@@ -27,34 +35,164 @@ pub async fn download_file<'a>(
             Err(e) => return Err(e.into_inner()),
         };
 
-        let mut problem: String = String::from("");
-        res.read_to_string(&mut problem).await?;
+        let mut reader = res.take_body().into_reader();
+        if offset > 0 && res.status() != StatusCode::PartialContent {
+            skip(&mut reader, offset).await?;
+        }
 
-        let download_id = get_download_id(problem.as_str())?;
-        let filename = get_filename(problem.as_str())?;
+        reader
+    };
 
-        (download_id, filename)
+    Ok(download_reader)
+}
+
+pub async fn file_metadata<'a>(
+    server_id: &'a str,
+    file_id: &'a str,
+) -> anyhow::Result<(String, Option<usize>)> {
+    let client = Client::new();
+
+    let (download_id, filename) = get_download_page(&client, server_id, file_id).await?;
+
+    let len = get_file_len(
+        &client,
+        server_id,
+        file_id,
+        download_id.as_str(),
+        filename.as_str(),
+    )
+    .await?;
+
+    Ok((filename, len))
+}
+
+async fn get_download_page<'a>(
+    client: &'a Client,
+    server_id: &'a str,
+    file_id: &'a str,
+) -> anyhow::Result<(String, String)> {
+    let problem = match get_file_page(client, server_id, file_id).await? {
+        Some(v) if !is_removed(v.as_str()) => v,
+        _ => return Err(anyhow::anyhow!("file does not exist.")),
     };
 
-    let download_reader = {
-        let req = {
+    let download_id = get_download_id(problem.as_str())?;
+    let filename = get_filename(problem.as_str())?;
+
+    Ok((download_id, filename))
+}
+
+async fn get_file_page<'a>(
+    client: &'a Client,
+    server_id: &'a str,
+    file_id: &'a str,
+) -> anyhow::Result<Option<String>> {
+    let req = {
+        let url = {
             let uri = format!(
-                "https://www{}.zippyshare.com/d/{}/{}/{}",
-                server_id, file_id, download_id, filename
+                "https://www{}.zippyshare.com/v/{}/file.html",
+                server_id, file_id
             );
             let url = Url::parse(uri.as_str())?;
-            Request::builder(Method::Get, url).build()
+            url
         };
+        Request::builder(Method::Get, url).build()
+    };
 
-        let mut res = match client.send(req).await {
-            Ok(res) => res,
-            Err(e) => return Err(e.into_inner()),
-        };
+    let mut res = match client.send(req).await {
+        Ok(res) => res,
+        Err(e) => return Err(e.into_inner()),
+    };
+    if res.status() == StatusCode::NotFound {
+        return Ok(None);
+    }
+
+    let mut problem: String = String::from("");
+    res.read_to_string(&mut problem).await?;
 
-        res.take_body().into_reader()
+    Ok(Some(problem))
+}
+
+pub async fn file_status<'a>(server_id: &'a str, file_id: &'a str) -> anyhow::Result<LinkStatus> {
+    let client = Client::new();
+
+    let problem = match get_file_page(&client, server_id, file_id).await? {
+        Some(v) => v,
+        None => return Ok(LinkStatus::Removed),
+    };
+    let (download_id, filename) = match page_status(problem.as_str()) {
+        Ok(v) => v,
+        Err(status) => return Ok(status),
     };
 
-    Ok(download_reader)
+    let len = get_file_len(
+        &client,
+        server_id,
+        file_id,
+        download_id.as_str(),
+        filename.as_str(),
+    )
+    .await?;
+
+    Ok(LinkStatus::Alive(FileMetadata {
+        name: filename,
+        len,
+    }))
+}
+
+async fn get_file_len<'a>(
+    client: &'a Client,
+    server_id: &'a str,
+    file_id: &'a str,
+    download_id: &'a str,
+    filename: &'a str,
+) -> anyhow::Result<Option<usize>> {
+    let req = {
+        let uri = format!(
+            "https://www{}.zippyshare.com/d/{}/{}/{}",
+            server_id, file_id, download_id, filename
+        );
+        let url = Url::parse(uri.as_str())?;
+        Request::builder(Method::Get, url)
+            .header("Range", "bytes=0-0")
+            .build()
+    };
+
+    let res = match client.send(req).await {
+        Ok(res) => res,
+        Err(e) => return Err(e.into_inner()),
+    };
+
+    let len = match res.status() {
+        StatusCode::PartialContent => res
+            .header("Content-Range")
+            .and_then(|v| v.last().as_str().rsplit_once('/'))
+            .and_then(|(_, v)| v.trim().parse().ok()),
+        _ => res
+            .header("Content-Length")
+            .and_then(|v| v.last().as_str().parse().ok()),
+    };
+
+    Ok(len)
+}
+
+fn page_status(problem: &str) -> Result<(String, String), LinkStatus> {
+    if is_removed(problem) {
+        return Err(LinkStatus::Removed);
+    }
+    let download_id = match get_download_id(problem) {
+        Ok(v) => v,
+        Err(e) => return Err(LinkStatus::Unknown(e.to_string())),
+    };
+    let filename = match get_filename(problem) {
+        Ok(v) => v,
+        Err(e) => return Err(LinkStatus::Unknown(e.to_string())),
+    };
+    Ok((download_id, filename))
+}
+
+fn is_removed(problem: &str) -> bool {
+    problem.contains(FILE_DOES_NOT_EXIST)
 }
 
 fn get_filename<'a>(problem: &'a str) -> anyhow::Result<String> {
@@ -104,7 +242,7 @@ mod tests {
         }];
 
         for testcase in testcases {
-            let mut buff = super::download_file(testcase.server_id, testcase.file_id).await?;
+            let mut buff = super::download_file(testcase.server_id, testcase.file_id, 0).await?;
             let mut data: Vec<u8> = vec![];
             buff.read_to_end(&mut data).await?;
             let sha256 = digest(data.as_slice());
@@ -136,6 +274,75 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn page_status_test() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            problem: &'a str,
+            solution: Result<(&'a str, &'a str), super::LinkStatus>,
+        }
+
+        let testcases = [
+            TestCase {
+                problem: "document.getElementById('dlbutton').href = \"/d/UfqlE33b/\" + (690628 % 51245 + 690628 % 913) + \"/Screenshot_20230113_040647.png\";",
+                solution: Ok(("24843", "Screenshot_20230113_040647.png")),
+            },
+            TestCase {
+                problem: "<div>File does not exist on this server</div>",
+                solution: Err(super::LinkStatus::Removed),
+            },
+            TestCase {
+                problem: "<html>maintenance</html>",
+                solution: Err(super::LinkStatus::Unknown(String::from(
+                    "unable to recognize the problem.",
+                ))),
+            },
+            TestCase {
+                problem: "document.getElementById('dlbutton').href = (690628 % 51245 + 690628 % 913);",
+                solution: Err(super::LinkStatus::Unknown(String::from(
+                    "unable to recognize the problem.",
+                ))),
+            },
+        ];
+
+        for testcase in testcases {
+            let solution = super::page_status(testcase.problem);
+            assert_eq!(
+                solution,
+                testcase
+                    .solution
+                    .map(|(id, name)| (id.to_string(), name.to_string()))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn is_removed_test() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            problem: &'a str,
+            solution: bool,
+        }
+        let function = super::is_removed;
+
+        let testcases = [
+            TestCase {
+                problem: "<div id=\"lrbox\">\n<div style=\"margin-left: 24px; margin-top: 20px; text-align: center; width: 303px; height: 105px;\">\n<div>File does not exist on this server</div>\n</div>",
+                solution: true,
+            },
+            TestCase {
+                problem: "\n\n\n<script type=\"text/javascript\">\n    document.getElementById('dlbutton').href = \"/d/UfqlE33b/\" + (690628 % 51245 + 690628 % 913) + \"/Screenshot_20230113_040647.png\";\n</script>",
+                solution: false,
+            },
+        ];
+
+        for testcase in testcases {
+            let solution = function(testcase.problem);
+            assert_eq!(solution, testcase.solution);
+        }
+        Ok(())
+    }
+
     #[test]
     fn get_download_id_test() -> anyhow::Result<()> {
         struct TestCase<'a> {
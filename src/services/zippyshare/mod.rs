@@ -7,12 +7,15 @@ use download::*;
 mod upload;
 use upload::*;
 
-use crate::{Download, Service, Upload};
+use crate::hash::{hash, HashAlgorithm};
+use crate::verify::verify_upload;
+use crate::{Download, DownloadRange, FileMetadata, LinkStatus, Metadata, Probe, Service, Upload};
 use async_trait::async_trait;
 use futures::io::AsyncBufRead;
 
 pub struct Zippyshare {}
 
+#[derive(Clone)]
 pub struct DownloadSetting {}
 
 impl Service for Zippyshare {
@@ -29,12 +32,46 @@ impl Download for Zippyshare {
         _setting: Self::DownloadSetting,
         _auth_token: &'a self::AuthToken,
     ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
-        download_file(file.get_server_id(), file.get_file_id()).await
+        download_file(file.get_server_id(), file.get_file_id(), 0).await
+    }
+}
+
+#[async_trait]
+impl DownloadRange for Zippyshare {
+    async fn download_range<'a>(
+        file: Self::File,
+        _setting: Self::DownloadSetting,
+        _auth_token: &'a self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        download_file(file.get_server_id(), file.get_file_id(), offset).await
+    }
+}
+
+#[async_trait]
+impl Metadata for Zippyshare {
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        _auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        let (name, len) = file_metadata(file.get_server_id(), file.get_file_id()).await?;
+        Ok(FileMetadata { name, len })
+    }
+}
+
+#[async_trait]
+impl Probe for Zippyshare {
+    async fn probe<'a>(
+        file: &'a Self::File,
+        _auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        file_status(file.get_server_id(), file.get_file_id()).await
     }
 }
 
 pub struct UploadSetting {
     pub private: bool,
+    pub verify: bool,
 }
 
 #[async_trait]
@@ -59,6 +96,12 @@ impl Upload for Zippyshare {
                 max_file_size,
             )));
         }
+        let (reader, handle) = if setting.verify {
+            let (reader, handle) = hash(reader, HashAlgorithm::Sha256);
+            (reader, Some(handle))
+        } else {
+            (reader, None)
+        };
         let uri = upload_file(
             name,
             reader,
@@ -68,6 +111,19 @@ impl Upload for Zippyshare {
             auth_token.zipname.as_str(),
         )
         .await?;
-        Self::File::try_from(uri)
+        let file = Self::File::try_from(uri)?;
+
+        if let Some(handle) = handle {
+            verify_upload::<Zippyshare>(
+                file.clone(),
+                DownloadSetting {},
+                auth_token,
+                handle.len(),
+                &handle.digest(),
+            )
+            .await?;
+        }
+
+        Ok(file)
     }
 }
@@ -1,4 +1,6 @@
-use crate::utils::{gen_boundary, Multipart, MultipartContentEnum, MultipartField};
+use crate::utils::{
+    gen_boundary, Multipart, MultipartContentEnum, MultipartField, BOUNDARY_CHARSET,
+};
 use futures::AsyncBufRead;
 use regex::Regex;
 use surf::http::Method;
@@ -41,10 +43,7 @@ pub async fn upload_file<'a>(
                 let uri = format!("https://www{}.zippyshare.com/upload", server_id);
                 Url::parse(uri.as_str())?
             };
-            let boundary = gen_boundary(
-                16,
-                "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-",
-            )?;
+            let boundary = gen_boundary(16, BOUNDARY_CHARSET)?;
             let content_type = format!("multipart/form-data; boundary={}", boundary);
             let body = {
                 let name_field = MultipartField {
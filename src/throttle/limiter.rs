@@ -0,0 +1,180 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct Bucket {
+    rate: f64,
+    burst: f64,
+    available: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(rate: f64, burst: f64) -> Bucket {
+        Bucket {
+            rate,
+            burst,
+            available: burst,
+            updated: Instant::now(),
+        }
+    }
+
+    fn take(&mut self, amount: f64) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.rate).min(self.burst);
+        self.updated = now;
+
+        self.available -= amount;
+        if self.available >= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(-self.available / self.rate)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Throttle {
+    requests: Option<Arc<Mutex<Bucket>>>,
+    bandwidth: Option<Arc<Mutex<Bucket>>>,
+}
+
+impl Throttle {
+    pub fn new() -> Throttle {
+        Throttle {
+            requests: None,
+            bandwidth: None,
+        }
+    }
+
+    pub fn requests_per_second(mut self, rate: f64) -> anyhow::Result<Throttle> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(anyhow::anyhow!("request rate {} must be positive.", rate));
+        }
+        self.requests = Some(Arc::new(Mutex::new(Bucket::new(rate, 1.0))));
+        Ok(self)
+    }
+
+    pub fn bytes_per_second(mut self, rate: usize) -> anyhow::Result<Throttle> {
+        if rate == 0 {
+            return Err(anyhow::anyhow!("byte rate must be positive."));
+        }
+        let rate = rate as f64;
+        self.bandwidth = Some(Arc::new(Mutex::new(Bucket::new(rate, rate))));
+        Ok(self)
+    }
+
+    pub async fn request(&self) {
+        if let Some(bucket) = &self.requests {
+            let delay = bucket.lock().unwrap().take(1.0);
+            wait(delay).await;
+        }
+    }
+
+    pub async fn consume(&self, len: usize) {
+        if let Some(bucket) = &self.bandwidth {
+            let delay = bucket.lock().unwrap().take(len as f64);
+            wait(delay).await;
+        }
+    }
+}
+
+async fn wait(delay: Duration) {
+    if !delay.is_zero() {
+        async_std::task::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Throttle;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn throttle() -> anyhow::Result<()> {
+        struct TestCase {
+            throttle: Throttle,
+            requests: usize,
+            bytes: Vec<usize>,
+            min: Duration,
+            max: Duration,
+        }
+
+        let testcases = [
+            TestCase {
+                throttle: Throttle::new(),
+                requests: 10,
+                bytes: vec![1 << 20; 10],
+                min: Duration::ZERO,
+                max: Duration::from_millis(50),
+            },
+            TestCase {
+                throttle: Throttle::new().requests_per_second(20.0)?,
+                requests: 3,
+                bytes: vec![],
+                min: Duration::from_millis(90),
+                max: Duration::from_millis(500),
+            },
+            TestCase {
+                throttle: Throttle::new().bytes_per_second(1000)?,
+                requests: 0,
+                bytes: vec![500, 500, 100],
+                min: Duration::from_millis(90),
+                max: Duration::from_millis(500),
+            },
+        ];
+
+        for testcase in testcases {
+            let start = Instant::now();
+            for _ in 0..testcase.requests {
+                testcase.throttle.request().await;
+            }
+            for len in testcase.bytes {
+                testcase.throttle.consume(len).await;
+            }
+            let elapsed = start.elapsed();
+            assert!(elapsed >= testcase.min, "{:?}", elapsed);
+            assert!(elapsed <= testcase.max, "{:?}", elapsed);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn throttle_invalid_rate() -> anyhow::Result<()> {
+        struct TestCase {
+            rate: f64,
+            ok: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                rate: 0.5,
+                ok: true,
+            },
+            TestCase {
+                rate: 0.0,
+                ok: false,
+            },
+            TestCase {
+                rate: -1.0,
+                ok: false,
+            },
+            TestCase {
+                rate: f64::NAN,
+                ok: false,
+            },
+            TestCase {
+                rate: f64::INFINITY,
+                ok: false,
+            },
+        ];
+
+        for testcase in testcases {
+            let throttle = Throttle::new().requests_per_second(testcase.rate);
+            assert_eq!(throttle.is_ok(), testcase.ok);
+        }
+        assert!(Throttle::new().bytes_per_second(0).is_err());
+        assert!(Throttle::new().bytes_per_second(1).is_ok());
+        Ok(())
+    }
+}
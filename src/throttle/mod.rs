@@ -0,0 +1,318 @@
+mod limiter;
+pub use limiter::*;
+
+use crate::utils::into_reader;
+use crate::{Download, DownloadRange, FileMetadata, LinkStatus, Metadata, Probe, Service, Upload};
+use async_trait::async_trait;
+use futures::io::AsyncBufRead;
+use futures::AsyncBufReadExt;
+use std::marker::PhantomData;
+
+pub struct Throttled<S> {
+    _service: PhantomData<S>,
+}
+
+pub struct AuthToken<T> {
+    pub inner: T,
+    pub throttles: Vec<Throttle>,
+}
+
+impl<T> AuthToken<T> {
+    pub fn new(inner: T) -> AuthToken<T> {
+        AuthToken {
+            inner,
+            throttles: vec![],
+        }
+    }
+
+    pub fn throttle(mut self, throttle: Throttle) -> AuthToken<T> {
+        self.throttles.push(throttle);
+        self
+    }
+}
+
+pub struct DownloadSetting<T> {
+    pub inner: T,
+    pub throttles: Vec<Throttle>,
+}
+
+pub struct UploadSetting<T> {
+    pub inner: T,
+    pub throttles: Vec<Throttle>,
+}
+
+pub fn throttle(
+    reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+    throttles: Vec<Throttle>,
+) -> Box<dyn AsyncBufRead + Send + Sync + Unpin> {
+    into_reader(futures::stream::unfold(
+        (reader, throttles),
+        |(mut reader, throttles)| async move {
+            let chunk = match reader.fill_buf().await {
+                Ok(v) => v.to_vec(),
+                Err(e) => return Some((Err(e), (reader, throttles))),
+            };
+            if chunk.is_empty() {
+                return None;
+            }
+            reader.consume_unpin(chunk.len());
+
+            for throttle in throttles.iter() {
+                throttle.consume(chunk.len()).await;
+            }
+            Some((Ok(chunk), (reader, throttles)))
+        },
+    ))
+}
+
+async fn request(throttles: &[Throttle]) {
+    for throttle in throttles {
+        throttle.request().await;
+    }
+}
+
+impl<S: Service> Service for Throttled<S> {
+    type AuthToken = AuthToken<S::AuthToken>;
+    type File = S::File;
+}
+
+#[async_trait]
+impl<S> Download for Throttled<S>
+where
+    S: Download,
+    S::File: Send,
+    S::DownloadSetting: Send,
+    S::AuthToken: Sync,
+{
+    type DownloadSetting = DownloadSetting<S::DownloadSetting>;
+
+    async fn download<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        let throttles = [setting.throttles, auth_token.throttles.clone()].concat();
+        request(&throttles).await;
+        let reader = S::download(file, setting.inner, &auth_token.inner).await?;
+        Ok(throttle(reader, throttles))
+    }
+}
+
+#[async_trait]
+impl<S> DownloadRange for Throttled<S>
+where
+    S: DownloadRange,
+    S::File: Send,
+    S::DownloadSetting: Send,
+    S::AuthToken: Sync,
+{
+    async fn download_range<'a>(
+        file: Self::File,
+        setting: Self::DownloadSetting,
+        auth_token: &'a Self::AuthToken,
+        offset: usize,
+    ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+        let throttles = [setting.throttles, auth_token.throttles.clone()].concat();
+        request(&throttles).await;
+        let reader = S::download_range(file, setting.inner, &auth_token.inner, offset).await?;
+        Ok(throttle(reader, throttles))
+    }
+}
+
+#[async_trait]
+impl<S> Metadata for Throttled<S>
+where
+    S: Metadata,
+    S::File: Sync,
+    S::AuthToken: Sync,
+{
+    async fn metadata<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<FileMetadata> {
+        request(&auth_token.throttles).await;
+        S::metadata(file, &auth_token.inner).await
+    }
+}
+
+#[async_trait]
+impl<S> Probe for Throttled<S>
+where
+    S: Probe,
+    S::File: Sync,
+    S::AuthToken: Sync,
+{
+    async fn probe<'a>(
+        file: &'a Self::File,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<LinkStatus> {
+        request(&auth_token.throttles).await;
+        S::probe(file, &auth_token.inner).await
+    }
+}
+
+#[async_trait]
+impl<S> Upload for Throttled<S>
+where
+    S: Upload,
+    S::UploadSetting: Send,
+    S::AuthToken: Sync,
+{
+    type UploadSetting = UploadSetting<S::UploadSetting>;
+
+    fn max_file_size(auth_token: &Self::AuthToken) -> usize {
+        S::max_file_size(&auth_token.inner)
+    }
+
+    async fn upload<'a>(
+        name: &'a str,
+        reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+        len: Option<usize>,
+        setting: Self::UploadSetting,
+        auth_token: &'a Self::AuthToken,
+    ) -> anyhow::Result<Self::File> {
+        let throttles = [setting.throttles, auth_token.throttles.clone()].concat();
+        request(&throttles).await;
+        let reader = throttle(reader, throttles);
+        S::upload(name, reader, len, setting.inner, &auth_token.inner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthToken, Throttle, Throttled, UploadSetting};
+    use crate::{FileMetadata, Metadata, Service, Upload};
+    use async_std::io::Cursor;
+    use async_trait::async_trait;
+    use futures::io::AsyncBufRead;
+    use futures::AsyncReadExt;
+    use std::time::{Duration, Instant};
+
+    struct Echo {}
+
+    impl Service for Echo {
+        type AuthToken = ();
+        type File = String;
+    }
+
+    #[async_trait]
+    impl Upload for Echo {
+        type UploadSetting = ();
+
+        fn max_file_size(_auth_token: &Self::AuthToken) -> usize {
+            usize::MAX
+        }
+
+        async fn upload<'a>(
+            _name: &'a str,
+            mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+            _len: Option<usize>,
+            _setting: Self::UploadSetting,
+            _auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Self::File> {
+            let mut data = String::new();
+            reader.read_to_string(&mut data).await?;
+            Ok(data)
+        }
+    }
+
+    #[async_trait]
+    impl Metadata for Echo {
+        async fn metadata<'a>(
+            file: &'a Self::File,
+            _auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<FileMetadata> {
+            Ok(FileMetadata {
+                name: file.clone(),
+                len: Some(file.len()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn throttled_upload() -> anyhow::Result<()> {
+        struct TestCase {
+            throttles: Vec<Throttle>,
+            uploads: usize,
+            min: Duration,
+        }
+
+        let global = Throttle::new().bytes_per_second(1000)?;
+        let testcases = [
+            TestCase {
+                throttles: vec![],
+                uploads: 2,
+                min: Duration::ZERO,
+            },
+            TestCase {
+                throttles: vec![global.clone(), Throttle::new().bytes_per_second(1 << 20)?],
+                uploads: 2,
+                min: Duration::from_millis(300),
+            },
+            TestCase {
+                throttles: vec![Throttle::new().requests_per_second(10.0)?],
+                uploads: 3,
+                min: Duration::from_millis(180),
+            },
+        ];
+
+        let data = "a".repeat(700);
+        let auth_token = AuthToken::new(());
+        for testcase in testcases {
+            let start = Instant::now();
+            for _ in 0..testcase.uploads {
+                let reader = Box::new(Cursor::new(data.clone()));
+                let setting = UploadSetting {
+                    inner: (),
+                    throttles: testcase.throttles.clone(),
+                };
+                let result = Throttled::<Echo>::upload(
+                    "name",
+                    reader,
+                    Some(data.len()),
+                    setting,
+                    &auth_token,
+                )
+                .await?;
+                assert_eq!(result, data);
+            }
+            assert!(start.elapsed() >= testcase.min, "{:?}", start.elapsed());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn throttled_metadata() -> anyhow::Result<()> {
+        struct TestCase {
+            auth_token: AuthToken<()>,
+            requests: usize,
+            min: Duration,
+        }
+
+        let testcases = [
+            TestCase {
+                auth_token: AuthToken::new(()),
+                requests: 3,
+                min: Duration::ZERO,
+            },
+            TestCase {
+                auth_token: AuthToken::new(()).throttle(Throttle::new().requests_per_second(10.0)?),
+                requests: 3,
+                min: Duration::from_millis(180),
+            },
+        ];
+
+        let file = String::from("abc");
+        for testcase in testcases {
+            let start = Instant::now();
+            for _ in 0..testcase.requests {
+                let metadata = Throttled::<Echo>::metadata(&file, &testcase.auth_token).await?;
+                assert_eq!(metadata.len, Some(3));
+            }
+            assert!(start.elapsed() >= testcase.min, "{:?}", start.elapsed());
+        }
+
+        Ok(())
+    }
+}
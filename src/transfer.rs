@@ -0,0 +1,144 @@
+use crate::{Download, Metadata, Upload};
+
+pub async fn transfer<Src, Dst>(
+    file: Src::File,
+    download_setting: Src::DownloadSetting,
+    src_auth_token: &Src::AuthToken,
+    upload_setting: Dst::UploadSetting,
+    dst_auth_token: &Dst::AuthToken,
+) -> anyhow::Result<Dst::File>
+where
+    Src: Download + Metadata,
+    Dst: Upload,
+{
+    let metadata = Src::metadata(&file, src_auth_token).await?;
+    if let Some(len) = metadata.len {
+        let max_file_size = Dst::max_file_size(dst_auth_token);
+        if len > max_file_size {
+            return Err(anyhow::anyhow!(
+                "{} is larger than {}.",
+                metadata.name,
+                max_file_size
+            ));
+        }
+    }
+
+    let reader = Src::download(file, download_setting, src_auth_token).await?;
+    Dst::upload(
+        metadata.name.as_str(),
+        reader,
+        metadata.len,
+        upload_setting,
+        dst_auth_token,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Download, FileMetadata, Metadata, Service, Upload};
+    use async_std::io::Cursor;
+    use async_trait::async_trait;
+    use futures::io::AsyncBufRead;
+    use futures::AsyncReadExt;
+
+    struct Source {}
+
+    impl Service for Source {
+        type AuthToken = ();
+        type File = (&'static str, &'static str);
+    }
+
+    #[async_trait]
+    impl Download for Source {
+        type DownloadSetting = ();
+
+        async fn download<'a>(
+            file: Self::File,
+            _setting: Self::DownloadSetting,
+            _auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+            Ok(Box::new(Cursor::new(file.1)))
+        }
+    }
+
+    #[async_trait]
+    impl Metadata for Source {
+        async fn metadata<'a>(
+            file: &'a Self::File,
+            _auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<FileMetadata> {
+            Ok(FileMetadata {
+                name: file.0.to_string(),
+                len: Some(file.1.len()),
+            })
+        }
+    }
+
+    struct Destination {}
+
+    impl Service for Destination {
+        type AuthToken = usize;
+        type File = String;
+    }
+
+    #[async_trait]
+    impl Upload for Destination {
+        type UploadSetting = ();
+
+        fn max_file_size(auth_token: &Self::AuthToken) -> usize {
+            *auth_token
+        }
+
+        async fn upload<'a>(
+            name: &'a str,
+            mut reader: Box<dyn AsyncBufRead + Send + Sync + Unpin>,
+            len: Option<usize>,
+            _setting: Self::UploadSetting,
+            _auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Self::File> {
+            let mut data = String::new();
+            reader.read_to_string(&mut data).await?;
+            Ok(format!("{}:{:?}:{}", name, len, data))
+        }
+    }
+
+    #[tokio::test]
+    async fn transfer() -> anyhow::Result<()> {
+        struct TestCase {
+            file: (&'static str, &'static str),
+            max_file_size: usize,
+            result: Option<&'static str>,
+        }
+
+        let testcases = [
+            TestCase {
+                file: ("name.txt", "abcd"),
+                max_file_size: 4,
+                result: Some("name.txt:Some(4):abcd"),
+            },
+            TestCase {
+                file: ("name.txt", "abcd"),
+                max_file_size: 3,
+                result: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let result = super::transfer::<Source, Destination>(
+                testcase.file,
+                (),
+                &(),
+                (),
+                &testcase.max_file_size,
+            )
+            .await;
+            match testcase.result {
+                Some(v) => assert_eq!(result?, v),
+                None => assert!(result.is_err()),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{Delete, DownloadRange, FileMetadata, LinkStatus, Metadata, Probe};
+use futures::AsyncReadExt;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn form(&self) -> BTreeMap<String, (Option<String>, Vec<u8>)> {
+        let mut form = BTreeMap::new();
+        let boundary = match self
+            .header("content-type")
+            .and_then(|v| v.split_once("boundary="))
+        {
+            Some((_, v)) => format!("--{}", v),
+            None => return form,
+        };
+        let body = String::from_utf8_lossy(self.body.as_slice()).to_string();
+        for part in body.split(boundary.as_str()) {
+            let (header, data) = match part.split_once("\r\n\r\n") {
+                Some(v) => v,
+                None => continue,
+            };
+            let field = |key: &str| {
+                header
+                    .split("; ")
+                    .find_map(|v| v.strip_prefix(format!("{}=\"", key).as_str()))
+                    .and_then(|v| v.split('"').next())
+                    .map(|v| v.to_string())
+            };
+            let name = match field("name") {
+                Some(v) => v,
+                None => continue,
+            };
+            let data = data.strip_suffix("\r\n").unwrap_or(data);
+            form.insert(name, (field("filename"), data.into()));
+        }
+        form
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16) -> MockResponse {
+        MockResponse {
+            status,
+            headers: vec![],
+            body: vec![],
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> MockResponse {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body<B: Into<Vec<u8>>>(mut self, body: B) -> MockResponse {
+        self.body = body.into();
+        self
+    }
+
+    pub fn ranged(req: &MockRequest, data: &[u8]) -> MockResponse {
+        let range = req
+            .header("range")
+            .and_then(|v| v.strip_prefix("bytes="))
+            .and_then(|v| v.split_once('-'));
+        match range {
+            Some((start, end)) => {
+                let start: usize = start.parse().unwrap();
+                let end: usize = end.parse().unwrap_or(data.len() - 1);
+                MockResponse::new(206)
+                    .header(
+                        "Content-Range",
+                        format!("bytes {}-{}/{}", start, end, data.len()).as_str(),
+                    )
+                    .body(&data[start..=end])
+            }
+            None => MockResponse::new(200).body(data),
+        }
+    }
+}
+
+pub fn serve<F>(handler: F) -> String
+where
+    F: Fn(MockRequest) -> MockResponse + Send + Sync + 'static,
+{
+    serve_with_base(move |_, req| handler(req))
+}
+
+pub fn serve_with_base<F>(handler: F) -> String
+where
+    F: Fn(&str, MockRequest) -> MockResponse + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let handler = {
+        let base = base.clone();
+        Arc::new(move |req| handler(base.as_str(), req))
+    };
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            let handler = handler.clone();
+            std::thread::spawn(move || {
+                let _ = handle(stream, handler.as_ref());
+            });
+        }
+    });
+
+    base
+}
+
+// Reads the uploaded `data` back in full and from its midpoint, then deletes it.
+pub async fn roundtrip<S>(
+    file: S::File,
+    setting: S::DownloadSetting,
+    auth_token: &S::AuthToken,
+    data: &[u8],
+    metadata: FileMetadata,
+) -> anyhow::Result<()>
+where
+    S: DownloadRange + Metadata + Probe + Delete,
+    S::File: Clone,
+    S::DownloadSetting: Clone,
+{
+    let mut read = vec![];
+    S::download(file.clone(), setting.clone(), auth_token)
+        .await?
+        .read_to_end(&mut read)
+        .await?;
+    assert_eq!(read, data);
+
+    let offset = data.len() / 2;
+    let mut read = vec![];
+    S::download_range(file.clone(), setting, auth_token, offset)
+        .await?
+        .read_to_end(&mut read)
+        .await?;
+    assert_eq!(read, &data[offset..]);
+
+    assert_eq!(S::metadata(&file, auth_token).await?, metadata);
+    assert_eq!(
+        S::probe(&file, auth_token).await?,
+        LinkStatus::Alive(metadata)
+    );
+
+    S::delete(file.clone(), auth_token).await?;
+    assert_eq!(S::probe(&file, auth_token).await?, LinkStatus::Removed);
+    Ok(())
+}
+
+fn handle<F>(stream: TcpStream, handler: &F) -> std::io::Result<()>
+where
+    F: Fn(MockRequest) -> MockResponse,
+{
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let mut parts = line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+
+        let mut headers = vec![];
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((k, v)) = line.split_once(':') {
+                headers.push((k.trim().to_lowercase(), v.trim().to_string()));
+            }
+        }
+        let mut request = MockRequest {
+            method,
+            path,
+            headers,
+            body: vec![],
+        };
+
+        if request.header("expect") == Some("100-continue") {
+            writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+        }
+        if let Some(len) = request.header("content-length") {
+            let len: usize = len.parse().unwrap_or(0);
+            let mut body = vec![0u8; len];
+            reader.read_exact(&mut body)?;
+            request.body = body;
+        } else if request.header("transfer-encoding") == Some("chunked") {
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line)?;
+                let len = usize::from_str_radix(line.trim(), 16).unwrap_or(0);
+                let mut chunk = vec![0u8; len + 2];
+                reader.read_exact(&mut chunk)?;
+                if len == 0 {
+                    break;
+                }
+                request.body.extend_from_slice(&chunk[..len]);
+            }
+        }
+
+        let head = request.method == "HEAD";
+        let response = handler(request);
+        let mut data = format!("HTTP/1.1 {} Mock\r\n", response.status);
+        for (k, v) in response.headers.iter() {
+            data.push_str(format!("{}: {}\r\n", k, v).as_str());
+        }
+        data.push_str(format!("Content-Length: {}\r\n\r\n", response.body.len()).as_str());
+        writer.write_all(data.as_bytes())?;
+        if !head {
+            writer.write_all(response.body.as_slice())?;
+        }
+        writer.flush()?;
+    }
+}
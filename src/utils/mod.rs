@@ -1,2 +1,16 @@
 mod multipart;
 pub use multipart::*;
+mod pipe;
+pub use pipe::*;
+mod request;
+pub use request::*;
+mod stream;
+pub use stream::*;
+mod transform;
+pub use transform::*;
+mod url;
+pub use url::*;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+pub use mock::*;
@@ -91,6 +91,9 @@ impl<'a> Multipart<'a> {
     }
 }
 
+pub const BOUNDARY_CHARSET: &str =
+    "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-";
+
 pub fn gen_boundary<'a>(length: usize, charset: &'a str) -> anyhow::Result<String> {
     if charset.is_empty() {
         return Err(anyhow::anyhow!("charset is empty"));
@@ -0,0 +1,112 @@
+use futures::channel::mpsc::{channel, Receiver, Sender};
+use futures::io::AsyncWrite;
+use futures::stream::IntoAsyncRead;
+use futures::{SinkExt, TryStreamExt};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+type Chunk = io::Result<Vec<u8>>;
+
+pub struct PipeWriter {
+    sender: Sender<Chunk>,
+}
+
+impl PipeWriter {
+    pub async fn fail(mut self, error: io::Error) {
+        let _ = self.sender.send(Err(error)).await;
+    }
+}
+
+impl AsyncWrite for PipeWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.sender.poll_ready(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(_e)) => return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+            Poll::Pending => return Poll::Pending,
+        }
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        match self.sender.start_send(Ok(buf.to_vec())) {
+            Ok(_) => Poll::Ready(Ok(buf.len())),
+            Err(_e) => Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.sender.close_channel();
+        Poll::Ready(Ok(()))
+    }
+}
+
+pub type PipeReader = IntoAsyncRead<Receiver<Chunk>>;
+
+pub fn pipe(capacity: usize) -> (PipeWriter, PipeReader) {
+    let (sender, receiver) = channel(capacity);
+    (PipeWriter { sender }, receiver.into_async_read())
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn pipe() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            capacity: usize,
+            chunks: Vec<&'a str>,
+            result: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                capacity: 0,
+                chunks: vec!["ab", "", "cd"],
+                result: "abcd",
+            },
+            TestCase {
+                capacity: 4,
+                chunks: vec![],
+                result: "",
+            },
+        ];
+
+        for testcase in testcases {
+            let (mut writer, mut reader) = super::pipe(testcase.capacity);
+            let chunks: Vec<String> = testcase.chunks.iter().map(|v| v.to_string()).collect();
+            let producer = async_std::task::spawn(async move {
+                for chunk in chunks {
+                    writer.write_all(chunk.as_bytes()).await?;
+                }
+                writer.close().await
+            });
+
+            let mut result = String::new();
+            reader.read_to_string(&mut result).await?;
+            producer.await?;
+            assert_eq!(result, testcase.result);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pipe_fail() -> anyhow::Result<()> {
+        let (writer, mut reader) = super::pipe(1);
+        async_std::task::spawn(writer.fail(std::io::ErrorKind::Other.into()));
+
+        let mut result = vec![];
+        assert!(reader.read_to_end(&mut result).await.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use surf::http::Method;
+use surf::{Body, Client, Request, RequestBuilder, Response, Url};
+
+pub async fn send<'a, F>(
+    client: &'a Client,
+    method: Method,
+    url: &'a Url,
+    headers: Vec<(&'a str, String)>,
+    body: Option<Body>,
+    authorize: F,
+) -> anyhow::Result<Response>
+where
+    F: FnOnce(RequestBuilder) -> RequestBuilder,
+{
+    let req = {
+        let mut req = authorize(Request::builder(method, url.clone()));
+        for (k, v) in headers.iter() {
+            req = req.header(*k, v.as_str());
+        }
+        if let Some(body) = body {
+            req = req.body(body);
+        }
+        req.build()
+    };
+
+    match client.send(req).await {
+        Ok(res) => Ok(res),
+        Err(e) => Err(e.into_inner()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::send;
+    use crate::utils::{serve, MockResponse};
+    use surf::http::Method;
+    use surf::{Client, Url};
+
+    #[tokio::test]
+    async fn send_headers() -> anyhow::Result<()> {
+        let addr = serve(|req| {
+            let echo = format!(
+                "{} {} {}",
+                req.method,
+                req.header("authorization").unwrap_or_default(),
+                req.header("x-extra").unwrap_or_default()
+            );
+            MockResponse::new(200).body(echo)
+        });
+
+        let client = Client::new();
+        let url = Url::parse(format!("{}/a", addr).as_str())?;
+        let headers = vec![("X-Extra", String::from("1"))];
+        let mut res = send(&client, Method::Get, &url, headers, None, |req| {
+            req.header("Authorization", "Bearer t")
+        })
+        .await?;
+        let body = match res.body_string().await {
+            Ok(v) => v,
+            Err(e) => return Err(e.into_inner()),
+        };
+        assert_eq!(body, "GET Bearer t 1");
+
+        Ok(())
+    }
+}
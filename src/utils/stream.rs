@@ -0,0 +1,108 @@
+use futures::io::AsyncBufRead;
+use futures::stream::Stream;
+use futures::{AsyncBufReadExt, StreamExt, TryStreamExt};
+use std::io;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+struct SyncStream<S> {
+    inner: Mutex<S>,
+}
+
+impl<S: Stream + Unpin> Stream for SyncStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.get_mut().inner.get_mut() {
+            Ok(v) => v.poll_next_unpin(cx),
+            Err(v) => v.into_inner().poll_next_unpin(cx),
+        }
+    }
+}
+
+pub fn into_reader<S>(stream: S) -> Box<dyn AsyncBufRead + Send + Sync + Unpin>
+where
+    S: Stream<Item = io::Result<Vec<u8>>> + Send + 'static,
+{
+    let stream = SyncStream {
+        inner: Mutex::new(Box::pin(stream)),
+    };
+    Box::new(stream.into_async_read())
+}
+
+pub async fn skip<R: AsyncBufRead + Unpin + ?Sized>(reader: &mut R, len: usize) -> io::Result<()> {
+    let mut remaining = len;
+    while remaining > 0 {
+        let data = reader.fill_buf().await?;
+        if data.is_empty() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let n = std::cmp::min(data.len(), remaining);
+        reader.consume_unpin(n);
+        remaining -= n;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+
+    #[tokio::test]
+    async fn into_reader() -> anyhow::Result<()> {
+        let chunks: Vec<std::io::Result<Vec<u8>>> = vec![Ok(b"ab".to_vec()), Ok(b"cd".to_vec())];
+        let mut reader = super::into_reader(futures::stream::iter(chunks));
+
+        let mut data = String::new();
+        reader.read_to_string(&mut data).await?;
+        assert_eq!(data, "abcd");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn skip() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            data: &'a str,
+            len: usize,
+            rest: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                data: "abcd",
+                len: 1,
+                rest: Some("bcd"),
+            },
+            TestCase {
+                data: "abcd",
+                len: 4,
+                rest: Some(""),
+            },
+            TestCase {
+                data: "abcd",
+                len: 5,
+                rest: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let mut reader = Cursor::new(testcase.data);
+            let result = super::skip(&mut reader, testcase.len).await;
+            match testcase.rest {
+                Some(v) => {
+                    result?;
+                    let mut rest = String::new();
+                    reader.read_to_string(&mut rest).await?;
+                    assert_eq!(rest, v);
+                }
+                None => assert!(result.is_err()),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use futures::io::{AsyncBufRead, AsyncRead};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub trait Transform {
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()>;
+    fn finish(&mut self, output: &mut Vec<u8>) -> io::Result<()>;
+}
+
+pub struct TransformReader<R, T> {
+    reader: R,
+    transform: T,
+    buffer: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R, T> TransformReader<R, T> {
+    pub fn new(reader: R, transform: T) -> TransformReader<R, T> {
+        TransformReader {
+            reader,
+            transform,
+            buffer: vec![],
+            position: 0,
+            finished: false,
+        }
+    }
+}
+
+impl<R: AsyncBufRead + Unpin, T: Transform + Unpin> AsyncBufRead for TransformReader<R, T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        while this.position >= this.buffer.len() && !this.finished {
+            this.buffer.clear();
+            this.position = 0;
+
+            let input = match Pin::new(&mut this.reader).poll_fill_buf(cx) {
+                Poll::Ready(Ok(v)) => v,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+            if input.is_empty() {
+                this.transform.finish(&mut this.buffer)?;
+                this.finished = true;
+            } else {
+                let len = input.len();
+                this.transform.update(input, &mut this.buffer)?;
+                Pin::new(&mut this.reader).consume(len);
+            }
+        }
+
+        Poll::Ready(Ok(&this.buffer[this.position..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.position = std::cmp::min(this.position + amt, this.buffer.len());
+    }
+}
+
+impl<R: AsyncBufRead + Unpin, T: Transform + Unpin> AsyncRead for TransformReader<R, T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let available = match self.as_mut().poll_fill_buf(cx) {
+            Poll::Ready(Ok(v)) => v,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let n = std::cmp::min(available.len(), buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::io::Cursor;
+    use futures::AsyncReadExt;
+    use std::io;
+
+    struct Upper {}
+
+    impl super::Transform for Upper {
+        fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+            output.extend(input.iter().map(|v| v.to_ascii_uppercase()));
+            Ok(())
+        }
+
+        fn finish(&mut self, output: &mut Vec<u8>) -> io::Result<()> {
+            output.extend_from_slice(b"!");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn transform_reader() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            result: &'a str,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "abcd",
+                result: "ABCD!",
+            },
+            TestCase {
+                src: "",
+                result: "!",
+            },
+        ];
+
+        for testcase in testcases {
+            let mut reader = super::TransformReader::new(Cursor::new(testcase.src), Upper {});
+            let mut result = String::new();
+            reader.read_to_string(&mut result).await?;
+            assert_eq!(result, testcase.result);
+        }
+
+        Ok(())
+    }
+}
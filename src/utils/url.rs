@@ -0,0 +1,57 @@
+pub fn percent_decode(src: &str) -> Option<String> {
+    let bytes = src.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = src.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn percent_decode() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            src: &'a str,
+            decoded: Option<&'a str>,
+        }
+
+        let testcases = [
+            TestCase {
+                src: "a%20b.txt",
+                decoded: Some("a b.txt"),
+            },
+            TestCase {
+                src: "%E2%82%AC",
+                decoded: Some("€"),
+            },
+            TestCase {
+                src: "plain",
+                decoded: Some("plain"),
+            },
+            TestCase {
+                src: "broken%2",
+                decoded: None,
+            },
+            TestCase {
+                src: "%zz",
+                decoded: None,
+            },
+        ];
+
+        for testcase in testcases {
+            let decoded = super::percent_decode(testcase.src);
+            assert_eq!(decoded.as_deref(), testcase.decoded);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use crate::hash::{Digest, Hasher, IntegrityError};
+use crate::Download;
+use futures::AsyncBufReadExt;
+
+pub async fn verify_upload<S: Download>(
+    file: S::File,
+    setting: S::DownloadSetting,
+    auth_token: &S::AuthToken,
+    len: usize,
+    digest: &Digest,
+) -> anyhow::Result<()> {
+    let mut reader = S::download(file, setting, auth_token).await?;
+    let mut hasher = Hasher::new(digest.get_algorithm());
+    let mut actual_len: usize = 0;
+
+    loop {
+        let data = reader.fill_buf().await?;
+        if data.is_empty() {
+            break;
+        }
+        let n = data.len();
+        hasher.update(data);
+        actual_len += n;
+        reader.consume_unpin(n);
+    }
+
+    if actual_len != len {
+        return Err(anyhow::anyhow!(
+            "size do not match, expected {} but got {}.",
+            len,
+            actual_len
+        ));
+    }
+
+    let actual = hasher.finalize();
+    if &actual != digest {
+        return Err(IntegrityError {
+            expected: digest.clone(),
+            actual,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hash::{Digest, IntegrityError};
+    use crate::{Download, Service};
+    use async_std::io::Cursor;
+    use async_trait::async_trait;
+    use futures::io::AsyncBufRead;
+
+    struct Fixed {}
+
+    impl Service for Fixed {
+        type AuthToken = ();
+        type File = &'static str;
+    }
+
+    #[async_trait]
+    impl Download for Fixed {
+        type DownloadSetting = ();
+
+        async fn download<'a>(
+            file: Self::File,
+            _setting: Self::DownloadSetting,
+            _auth_token: &'a Self::AuthToken,
+        ) -> anyhow::Result<Box<dyn AsyncBufRead + Send + Sync + Unpin>> {
+            Ok(Box::new(Cursor::new(file)))
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_upload() -> anyhow::Result<()> {
+        struct TestCase<'a> {
+            file: &'static str,
+            len: usize,
+            digest: &'a str,
+            status: bool,
+            integrity: bool,
+        }
+
+        let testcases = [
+            TestCase {
+                file: "abcd",
+                len: 4,
+                digest: "md5:e2fc714c4727ee9395f324cd2e7f331f",
+                status: true,
+                integrity: true,
+            },
+            TestCase {
+                file: "abc",
+                len: 4,
+                digest: "md5:e2fc714c4727ee9395f324cd2e7f331f",
+                status: false,
+                integrity: true,
+            },
+            TestCase {
+                file: "abce",
+                len: 4,
+                digest: "md5:e2fc714c4727ee9395f324cd2e7f331f",
+                status: false,
+                integrity: false,
+            },
+        ];
+
+        for testcase in testcases {
+            let digest = Digest::try_from(testcase.digest)?;
+            let result =
+                super::verify_upload::<Fixed>(testcase.file, (), &(), testcase.len, &digest).await;
+
+            if testcase.status {
+                result?;
+            } else {
+                let error = result.unwrap_err();
+                assert_eq!(
+                    error.downcast_ref::<IntegrityError>().is_none(),
+                    testcase.integrity
+                );
+            }
+        }
+
+        Ok(())
+    }
+}